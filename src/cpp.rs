@@ -1,29 +1,105 @@
 use crate::wrapper::base::*;
 
 pub(crate) fn cpp_code_base() -> String {
+    let primitive_aliases = C_PRIMITIVE_TYPES
+        .iter()
+        .map(|(rust_type, c_type)| format!("using {rust_type} = {c_type};\n"))
+        .collect::<String>();
     format!(
         r#"
 #ifndef BASE_H
 #define BASE_H
 
+#include <array>
 #include <cstdint>
 #include <cstddef>
+#include <cstdlib>
+#include <cstring>
+#include <functional>
+#include <map>
+#include <memory>
+#include <optional>
+#if __cplusplus >= 202002L
+#include <span>
+#endif
+#include <stdexcept>
 #include <string>
+#include <string_view>
+#include <tuple>
+#include <unordered_map>
+#include <utility>
+#include <variant>
+#include <vector>
+
+{primitive_aliases}
+// 128-bit integers are passed as their 64-bit words, as their C ABI is not portable
+struct FfiI128 {{
+    u64 lo;
+    i64 hi;
+}};
+
+struct FfiU128 {{
+    u64 lo;
+    u64 hi;
+}};
 
-using u8 = uint8_t;
-using u16 = uint16_t;
-using u32 = uint32_t;
-using u64 = uint64_t;
+#ifdef __SIZEOF_INT128__
+__extension__ typedef __int128 i128;
+__extension__ typedef unsigned __int128 u128;
 
-using i8 = int8_t;
-using i16 = int16_t;
-using i32 = int32_t;
-using i64 = int64_t;
+inline FfiI128 int128_to_ffi(i128 value) {{
+    return FfiI128{{static_cast<u64>(value), static_cast<i64>(value >> 64)}};
+}}
 
-using f32 = float;
-using f64 = double;
+inline FfiU128 int128_to_ffi(u128 value) {{
+    return FfiU128{{static_cast<u64>(value), static_cast<u64>(value >> 64)}};
+}}
 
-using usize = size_t;
+inline i128 int128_from_ffi(FfiI128 value) {{
+    return static_cast<i128>(static_cast<u128>(value.hi) << 64 | value.lo);
+}}
+
+inline u128 int128_from_ffi(FfiU128 value) {{
+    return static_cast<u128>(value.hi) << 64 | value.lo;
+}}
+#else
+// compilers without 128-bit integers, like MSVC, expose the words
+using i128 = FfiI128;
+using u128 = FfiU128;
+
+inline FfiI128 int128_to_ffi(FfiI128 value) {{
+    return value;
+}}
+
+inline FfiU128 int128_to_ffi(FfiU128 value) {{
+    return value;
+}}
+
+inline FfiI128 int128_from_ffi(FfiI128 value) {{
+    return value;
+}}
+
+inline FfiU128 int128_from_ffi(FfiU128 value) {{
+    return value;
+}}
+#endif
+
+// Values which are not valid for their Rust types are rejected before they are passed to Rust
+[[noreturn]] inline void reject_invalid_value(const char* message) {{
+#if defined(__cpp_exceptions)
+    throw std::invalid_argument(message);
+#else
+    std::abort();
+#endif
+}}
+
+// Surrogates and values above U+10FFFF are not valid Rust `char`s
+inline u32 char_to_ffi(char32_t value) {{
+    if (value > 0x10FFFF || (value >= 0xD800 && value <= 0xDFFF)) {{
+        reject_invalid_value("Invalid char value");
+    }}
+    return static_cast<u32>(value);
+}}
 
 extern "C" {{
     void {SLICE_DROP_FN_NAME}(void*);
@@ -33,6 +109,10 @@ extern "C" {{
     void {RUST_STRING_DROP_FN_NAME}(void*);
     char* {RUST_STRING_DATA_FN_NAME}(void*);
     usize {RUST_STRING_LEN_FN_NAME}(void*);
+
+    usize {MAP_ITER_LEN_FN_NAME}(void*);
+    bool {MAP_ITER_NEXT_FN_NAME}(void*, void*, void*);
+    void {MAP_ITER_DROP_FN_NAME}(void*);
 }}
 
 class RustString {{
@@ -49,6 +129,295 @@ public:
     }}
 }};
 
+struct FfiSlice {{
+    const void* ptr;
+    usize len;
+    void (*drop)(const void*, usize);
+}};
+
+// Rust strings have to be UTF-8, without overlong encodings, surrogates and values above U+10FFFF
+inline bool is_utf8(std::string_view value) {{
+    static const u32 min_code_points[] = {{0, 0, 0x80, 0x800, 0x10000}};
+    usize i = 0;
+    while (i < value.size()) {{
+        u8 byte = static_cast<u8>(value[i]);
+        usize len = byte < 0x80 ? 1 : (byte & 0xE0) == 0xC0 ? 2 : (byte & 0xF0) == 0xE0 ? 3 : (byte & 0xF8) == 0xF0 ? 4 : 0;
+        if (len == 0 || value.size() - i < len) {{
+            return false;
+        }}
+        if (len > 1) {{
+            u32 code_point = byte & (0x7F >> len);
+            for (usize j = 1; j < len; j++) {{
+                u8 next = static_cast<u8>(value[i + j]);
+                if ((next & 0xC0) != 0x80) {{
+                    return false;
+                }}
+                code_point = code_point << 6 | (next & 0x3F);
+            }}
+            if (code_point < min_code_points[len] || code_point > 0x10FFFF || (code_point >= 0xD800 && code_point <= 0xDFFF)) {{
+                return false;
+            }}
+        }}
+        i += len;
+    }}
+    return true;
+}}
+
+inline FfiSlice str_to_ffi(std::string_view value) {{
+    if (!is_utf8(value)) {{
+        reject_invalid_value("Invalid UTF-8 string");
+    }}
+    return FfiSlice{{value.data(), value.size(), nullptr}};
+}}
+
+inline const char* string_to_ffi(const std::string& value) {{
+    if (!is_utf8(value)) {{
+        reject_invalid_value("Invalid UTF-8 string");
+    }}
+    return value.c_str();
+}}
+
+struct FfiBuffer {{
+    u8* ptr;
+    usize len;
+}};
+
+extern "C" {{
+    void {BUFFER_DROP_FN_NAME}(FfiBuffer);
+}}
+
+// Copies bytes returned from Rust and frees them
+inline std::vector<u8> vec_from_buffer(FfiBuffer buffer) {{
+    std::vector<u8> result(buffer.ptr, buffer.ptr + buffer.len);
+    {BUFFER_DROP_FN_NAME}(buffer);
+    return result;
+}}
+
+// Copies an array of primitives into its FFI struct `A` in a single call
+template <typename A, typename T, std::size_t N>
+A array_to_ffi(const std::array<T, N>& values) {{
+    A array;
+    std::memcpy(array.items, values.data(), sizeof(array.items));
+    return array;
+}}
+
+// Converts every item of an array into its FFI struct `A`
+template <typename A, typename T, std::size_t N, typename F>
+A array_to_ffi(const std::array<T, N>& values, F convert) {{
+    A array;
+    for (std::size_t i = 0; i < N; i++) {{
+        array.items[i] = convert(values[i]);
+    }}
+    return array;
+}}
+
+// Copies an array of primitives returned from Rust in a single call
+template <typename T, std::size_t N>
+std::array<T, N> array_from_ffi(const T (&items)[N]) {{
+    std::array<T, N> array;
+    std::memcpy(array.data(), items, sizeof(items));
+    return array;
+}}
+
+template <typename T, typename W, std::size_t N, typename F, std::size_t... I>
+std::array<T, N> array_from_ffi(const W (&items)[N], F convert, std::index_sequence<I...>) {{
+    return {{convert(items[I])...}};
+}}
+
+// Converts every item of an array returned from Rust, taking ownership of them
+template <typename T, typename W, std::size_t N, typename F>
+std::array<T, N> array_from_ffi(const W (&items)[N], F convert) {{
+    return array_from_ffi<T>(items, convert, std::make_index_sequence<N>());
+}}
+
+// Holds converted items of a vector passed to Rust, which copies them before the call returns
+template <typename W>
+class SliceArg {{
+    W* items;
+    usize len;
+public:
+    template <typename T, typename F>
+    SliceArg(const std::vector<T>& values, F convert) : items(new W[values.size()]), len(values.size()) {{
+        for (usize i = 0; i < len; i++) {{
+            items[i] = convert(values[i]);
+        }}
+    }}
+    template <typename T, typename F>
+    SliceArg(const std::optional<T>& value, F convert) : items(new W[value ? 1 : 0]), len(value ? 1 : 0) {{
+        if (value) {{
+            items[0] = convert(*value);
+        }}
+    }}
+    SliceArg(const SliceArg&) = delete;
+    ~SliceArg() {{
+        delete[] items;
+    }}
+    FfiSlice slice() const {{
+        return FfiSlice{{items, len, nullptr}};
+    }}
+}};
+
+struct FfiMap {{
+    FfiSlice keys;
+    FfiSlice values;
+}};
+
+// Holds converted entries of a map passed to Rust, which copies them before the call returns
+template <typename KW, typename VW>
+class MapArg {{
+    KW* keys;
+    VW* values;
+    usize len;
+public:
+    template <typename M, typename FK, typename FV>
+    MapArg(const M& map, FK convert_key, FV convert_value)
+        : keys(new KW[map.size()]), values(new VW[map.size()]), len(map.size()) {{
+        usize i = 0;
+        for (const auto& [key, value] : map) {{
+            keys[i] = convert_key(key);
+            values[i] = convert_value(value);
+            i++;
+        }}
+    }}
+    MapArg(const MapArg&) = delete;
+    ~MapArg() {{
+        delete[] keys;
+        delete[] values;
+    }}
+    FfiMap map() const {{
+        return FfiMap{{FfiSlice{{keys, len, nullptr}}, FfiSlice{{values, len, nullptr}}}};
+    }}
+}};
+
+struct FfiError {{
+    void* message;
+    void* variant;
+    i64 repr;
+}};
+
+namespace hi_ffi {{
+
+#if __cplusplus >= 202002L
+using ByteSpan = std::span<const u8>;
+#else
+// Bytes borrowed by Rust for the duration of a call, `std::span<const u8>` since C++20
+class ByteSpan {{
+    const u8* ptr = nullptr;
+    usize len = 0;
+public:
+    ByteSpan() = default;
+    ByteSpan(const u8* ptr, usize len) : ptr(ptr), len(len) {{}}
+    ByteSpan(const std::vector<u8>& bytes) : ptr(bytes.data()), len(bytes.size()) {{}}
+
+    const u8* data() const {{
+        return this->ptr;
+    }}
+    usize size() const {{
+        return this->len;
+    }}
+}};
+#endif
+
+// Error returned from Rust function, it carries `Display` representation of the Rust error
+class RustError : public std::runtime_error {{
+public:
+    explicit RustError(const std::string& message) : std::runtime_error(message) {{}}
+
+    std::string message() const {{
+        return this->what();
+    }}
+}};
+
+// Result of a Rust function returning `Result` when exceptions are disabled (`cpp-expected` feature)
+template <typename T>
+class Expected {{
+    std::variant<T, RustError> value;
+public:
+    Expected(T value) : value(std::in_place_index<0>, std::move(value)) {{}}
+    Expected(RustError error) : value(std::in_place_index<1>, std::move(error)) {{}}
+
+    bool has_value() const {{
+        return this->value.index() == 0;
+    }}
+    explicit operator bool() const {{
+        return this->has_value();
+    }}
+    T& operator*() {{
+        return *std::get_if<0>(&this->value);
+    }}
+    T* operator->() {{
+        return std::get_if<0>(&this->value);
+    }}
+    const RustError& error() const {{
+        return *std::get_if<1>(&this->value);
+    }}
+}};
+
+template <>
+class Expected<void> {{
+    std::optional<RustError> value;
+public:
+    Expected() {{}}
+    Expected(RustError error) : value(std::move(error)) {{}}
+
+    bool has_value() const {{
+        return !this->value.has_value();
+    }}
+    explicit operator bool() const {{
+        return this->has_value();
+    }}
+    const RustError& error() const {{
+        return *this->value;
+    }}
+}};
+
+}}
+
+// Views `&'static str` returned from Rust
+inline std::string_view str_from_slice(FfiSlice slice) {{
+    return std::string_view(static_cast<const char*>(slice.ptr), slice.len);
+}}
+
+// Converts item of a slice with zero or one item returned from Rust and frees it
+template <typename W, typename F>
+auto optional_from_slice(void* slice, F convert) {{
+    auto ffi_slice = static_cast<FfiSlice*>(slice);
+    auto items = static_cast<const W*>(ffi_slice->ptr);
+    std::optional<decltype(convert(std::declval<W>()))> result;
+    if (ffi_slice->len > 0) {{
+        result.emplace(convert(items[0]));
+    }}
+    {SLICE_DROP_FN_NAME}(slice);
+    return result;
+}}
+
+// Converts items of a slice returned from Rust and frees it
+template <typename W, typename F>
+auto vec_from_slice(void* slice, F convert) {{
+    auto ffi_slice = static_cast<FfiSlice*>(slice);
+    auto items = static_cast<const W*>(ffi_slice->ptr);
+    std::vector<decltype(convert(std::declval<W>()))> result;
+    result.reserve(ffi_slice->len);
+    for (usize i = 0; i < ffi_slice->len; i++) {{
+        result.push_back(convert(items[i]));
+    }}
+    {SLICE_DROP_FN_NAME}(slice);
+    return result;
+}}
+
+// Converts entries of a map iterator returned from Rust one by one and frees it
+template <typename M, typename KW, typename VW, typename FK, typename FV>
+M map_from_iter(void* iter, FK convert_key, FV convert_value) {{
+    M result;
+    KW key;
+    VW value;
+    while ({MAP_ITER_NEXT_FN_NAME}(iter, &key, &value)) {{
+        result.emplace(convert_key(key), convert_value(value));
+    }}
+    {MAP_ITER_DROP_FN_NAME}(iter);
+    return result;
+}}
+
 #endif
 
 "#
@@ -8,7 +8,7 @@ use proc_macro2::TokenStream as TokenStream2;
 use swift::*;
 use syn::{parse_macro_input, Item};
 use translator::translate;
-use wrapper::{base::rust_code_base, Wrapper};
+use wrapper::{base::rust_code_base, ParsedWrapper, Wrapper};
 
 #[cfg(feature = "cpp")]
 mod cpp;
@@ -30,9 +30,9 @@ static RUST_CODE_BASE_GENERATED: Once = Once::new();
 static SWIFT_C_HEADER_RECREATED: Once = Once::new();
 
 #[proc_macro_attribute]
-pub fn ffi(_attr: TokenStream, input: TokenStream) -> TokenStream {
+pub fn ffi(attr: TokenStream, input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as Item);
-    let wrapper = translate(input);
+    let wrapper = translate(attr.into(), input);
 
     write_rust_code(&wrapper);
     #[cfg(feature = "cpp")]
@@ -82,13 +82,17 @@ fn write_swift_code(wrapper: &Wrapper) {
         write_to_file(swift_code_base(), swift_code_base_path);
     });
 
-    let crate::wrapper::SwiftFiles { header, source } = wrapper.swift();
+    for crate::wrapper::SwiftFiles {
+        name,
+        header,
+        source,
+    } in wrapper.swift()
+    {
+        append_to_file(header, &swift_header_path);
 
-    append_to_file(header, swift_header_path);
-
-    let source_file_name = format!("{}.swift", wrapper.name());
-    let source_path = ffi_module_path.join(source_file_name);
-    write_to_file(source, source_path);
+        let source_path = ffi_module_path.join(format!("{name}.swift"));
+        write_to_file(source, source_path);
+    }
 }
 
 fn write_rust_code(wrapper: &Wrapper) {
@@ -100,7 +104,11 @@ fn write_rust_code(wrapper: &Wrapper) {
     write_to_file(rust_code_base(), rust_base_path);
     // }
 
-    let file_name = format!("{}.rs", wrapper.name());
+    // impl blocks are generated next to the struct they belong to
+    let file_name = match wrapper.parsed {
+        ParsedWrapper::Impl(_) => format!("{}__impl.rs", wrapper.name()),
+        _ => format!("{}.rs", wrapper.name()),
+    };
     let rust_tokens: TokenStream2 = wrapper.into();
     write_to_file(&rust_tokens, rust_path.join(file_name));
 }
@@ -115,12 +123,16 @@ fn write_cpp_code(wrapper: &Wrapper) {
     write_to_file(cpp_code_base(), code_base_path);
     // }
 
-    let header_file_name = format!("{}.h", wrapper.name());
-    let source_file_name = format!("{}.cpp", wrapper.name());
-    let crate::wrapper::CppFiles { header, source } = wrapper.cpp();
-    write_to_file(header, cpp_path.join(header_file_name));
-    if let Some(source) = source {
-        write_to_file(source, cpp_path.join(source_file_name));
+    for crate::wrapper::CppFiles {
+        name,
+        header,
+        source,
+    } in wrapper.cpp()
+    {
+        write_to_file(header, cpp_path.join(format!("{name}.h")));
+        if let Some(source) = source {
+            write_to_file(source, cpp_path.join(format!("{name}.cpp")));
+        }
     }
 }
 
@@ -8,6 +8,40 @@ import Foundation
 
 public typealias bool = Bool
 
+// 128-bit integers as their 64-bit words, the high word carries the sign of `i128`
+public typealias i128 = FfiI128
+public typealias u128 = FfiU128
+
+extension FfiI128: Hashable {{
+    public init(_ value: Int64) {{
+        self.init(lo: UInt64(bitPattern: value), hi: value < 0 ? -1 : 0)
+    }}
+
+    public static func == (lhs: FfiI128, rhs: FfiI128) -> Bool {{
+        return lhs.lo == rhs.lo && lhs.hi == rhs.hi
+    }}
+
+    public func hash(into hasher: inout Hasher) {{
+        hasher.combine(self.lo)
+        hasher.combine(self.hi)
+    }}
+}}
+
+extension FfiU128: Hashable {{
+    public init(_ value: UInt64) {{
+        self.init(lo: value, hi: 0)
+    }}
+
+    public static func == (lhs: FfiU128, rhs: FfiU128) -> Bool {{
+        return lhs.lo == rhs.lo && lhs.hi == rhs.hi
+    }}
+
+    public func hash(into hasher: inout Hasher) {{
+        hasher.combine(self.lo)
+        hasher.combine(self.hi)
+    }}
+}}
+
 open class Opaque {{
     private var _self: UnsafeMutableRawPointer
 
@@ -18,6 +52,10 @@ open class Opaque {{
     open func rawPtr() -> UnsafeMutableRawPointer {{
         return self._self
     }}
+
+    open func ffiPtr(_ args: FfiArgs) -> UnsafeMutableRawPointer {{
+        return self.rawPtr()
+    }}
 }}
 
 public class RustString: Opaque {{
@@ -34,36 +72,208 @@ public class RustString: Opaque {{
     }}
 }}
 
+public class FfiArgs {{
+    private var releases: [() -> Void] = []
+
+    public init() {{}}
+
+    public func string(_ value: String) -> UnsafePointer<CChar> {{
+        let ptr = strdup(value)!
+        self.onRelease {{ free(ptr) }}
+        return UnsafePointer(ptr)
+    }}
+
+    public func slice<T>(_ values: [T]) -> FfiSlice {{
+        let ptr = UnsafeMutablePointer<T>.allocate(capacity: values.count)
+        ptr.initialize(from: values, count: values.count)
+        self.onRelease {{
+            ptr.deinitialize(count: values.count)
+            ptr.deallocate()
+        }}
+        return FfiSlice(ptr: UnsafeRawPointer(ptr), len: values.count, drop: nil)
+    }}
+
+    public func bytes(_ value: Data) -> FfiSlice {{
+        return self.slice([UInt8](value))
+    }}
+
+    public func str(_ value: String) -> FfiSlice {{
+        return self.slice(Array(value.utf8))
+    }}
+
+    public func map(_ keys: FfiSlice, _ values: FfiSlice) -> FfiMap {{
+        return FfiMap(keys: keys, values: values)
+    }}
+
+    public func optional<T>(_ value: T?) -> FfiSlice {{
+        return self.slice(value.map {{ [$0] }} ?? [])
+    }}
+
+    public func onRelease(_ release: @escaping () -> Void) {{
+        self.releases.append(release)
+    }}
+
+    public func release() {{
+        self.releases.forEach {{ $0() }}
+        self.releases.removeAll()
+    }}
+}}
+
+// Keeps a closure passed to Rust alive until Rust drops it and calls `releaseCallbackContext`
+public class CallbackContext<F> {{
+    private let callback: F
+
+    public init(_ callback: F) {{
+        self.callback = callback
+    }}
+
+    public func retain() -> UnsafeMutableRawPointer {{
+        return Unmanaged.passRetained(self).toOpaque()
+    }}
+
+    public static func callback(_ context: UnsafeMutableRawPointer?) -> F {{
+        return Unmanaged<CallbackContext<F>>.fromOpaque(context!).takeUnretainedValue().callback
+    }}
+}}
+
+public func releaseCallbackContext(_ context: UnsafeMutableRawPointer?) {{
+    Unmanaged<AnyObject>.fromOpaque(context!).release()
+}}
+
+// Error returned from Rust function, it carries `Display` representation of the Rust error
+public struct RustError: Error, CustomStringConvertible {{
+    public let message: String
+
+    public var description: String {{
+        return self.message
+    }}
+}}
+
+// Error returned from Rust function with `#[ffi]` enum error type
+public struct RustEnumError<E>: Error, CustomStringConvertible {{
+    public let message: String
+    public let variant: E
+
+    public var description: String {{
+        return self.message
+    }}
+}}
+
+// Copies bytes returned from Rust and frees them
+public func dataFromRustBuffer(_ buffer: FfiBuffer) -> Data {{
+    let data = buffer.len == 0 ? Data() : Data(bytes: buffer.ptr!, count: buffer.len)
+    {BUFFER_DROP_FN_NAME}(buffer)
+    return data
+}}
+
+// Copies items of a fixed-size array into its FFI struct, which consists of the items only
+public func arrayToFfi<A, T>(_ items: [T], _ array: A) -> A {{
+    precondition(items.count * MemoryLayout<T>.stride == MemoryLayout<A>.size, "Unexpected array length")
+    var array = array
+    withUnsafeMutableBytes(of: &array) {{ bytes in
+        items.withUnsafeBytes {{ bytes.copyMemory(from: $0) }}
+    }}
+    return array
+}}
+
+// Copies items of a fixed-size array returned from Rust
+public func arrayFromFfi<A, T>(_ array: A, _ type: T.Type) -> [T] {{
+    return withUnsafeBytes(of: array) {{ Array($0.bindMemory(to: T.self)) }}
+}}
+
+// Copies `&'static str` returned from Rust
+public func stringFromRustStr(_ slice: FfiSlice) -> String {{
+    return String(decoding: UnsafeRawBufferPointer(start: slice.ptr, count: slice.len), as: UTF8.self)
+}}
+
+public func fromRustSlice<W, T>(_ slice: UnsafeMutableRawPointer, _ convert: (W) -> T) -> [T] {{
+    let ffiSlice = slice.assumingMemoryBound(to: FfiSlice.self).pointee
+    let items = UnsafeBufferPointer(start: ffiSlice.ptr?.assumingMemoryBound(to: W.self), count: ffiSlice.len)
+    let result = items.map(convert)
+    {SLICE_DROP_FN_NAME}(slice)
+    return result
+}}
+
+// Converts entries of a map iterator returned from Rust one by one and frees it
+public func fromRustMap<KW, VW, K: Hashable, V>(
+    _ iter: UnsafeMutableRawPointer,
+    _ convertKey: (KW) -> K,
+    _ convertValue: (VW) -> V
+) -> [K: V] {{
+    var result = [K: V](minimumCapacity: Int({MAP_ITER_LEN_FN_NAME}(iter)))
+    let key = UnsafeMutablePointer<KW>.allocate(capacity: 1)
+    let value = UnsafeMutablePointer<VW>.allocate(capacity: 1)
+    while {MAP_ITER_NEXT_FN_NAME}(iter, key, value) {{
+        result[convertKey(key.pointee)] = convertValue(value.pointee)
+    }}
+    key.deallocate()
+    value.deallocate()
+    {MAP_ITER_DROP_FN_NAME}(iter)
+    return result
+}}
+
 "#
     )
 }
 
 pub(crate) fn swift_c_header_code_base() -> String {
+    let primitive_typedefs = C_PRIMITIVE_TYPES
+        .iter()
+        .map(|(rust_type, c_type)| format!("typedef {c_type} {rust_type};\n"))
+        .collect::<String>();
     format!(
         r#"
+#include <stddef.h>
 #include <stdint.h>
 #include <stdbool.h>
 
-typedef uint8_t u8;
-typedef uint16_t u16;
-typedef uint32_t u32;
-typedef uint64_t u64;
+{primitive_typedefs}
+// 128-bit integers are passed as their 64-bit words, as their C ABI is not portable
+typedef struct FfiI128 {{
+    u64 lo;
+    i64 hi;
+}} FfiI128;
+
+typedef struct FfiU128 {{
+    u64 lo;
+    u64 hi;
+}} FfiU128;
 
-typedef int8_t i8;
-typedef int16_t i16;
-typedef int32_t i32;
-typedef int64_t i64;
+typedef struct FfiSlice {{
+    const void* ptr;
+    usize len;
+    void (*drop)(const void*, usize);
+}} FfiSlice;
 
-typedef float f32;
-typedef double f64;
+typedef struct FfiBuffer {{
+    u8* ptr;
+    usize len;
+}} FfiBuffer;
+
+typedef struct FfiMap {{
+    FfiSlice keys;
+    FfiSlice values;
+}} FfiMap;
+
+typedef struct FfiError {{
+    void* message;
+    void* variant;
+    i64 repr;
+}} FfiError;
 
 void* {RUST_STRING_DATA_FN_NAME}(void* self);
-unsigned int {RUST_STRING_LEN_FN_NAME}(void* self);
+usize {RUST_STRING_LEN_FN_NAME}(void* self);
 void {RUST_STRING_DROP_FN_NAME}(void* self);
 
 void* {SLICE_GET_PTR_FN_NAME}(void* self);
-unsigned int {SLICE_GET_LEN_FN_NAME}(void* self);
+usize {SLICE_GET_LEN_FN_NAME}(void* self);
 void {SLICE_DROP_FN_NAME}(void* self);
+
+void {BUFFER_DROP_FN_NAME}(FfiBuffer buffer);
+
+usize {MAP_ITER_LEN_FN_NAME}(void* iter);
+bool {MAP_ITER_NEXT_FN_NAME}(void* iter, void* key, void* value);
+void {MAP_ITER_DROP_FN_NAME}(void* iter);
 "#
     )
 }
@@ -1,19 +1,43 @@
 use core::panic;
 use std::any::Any;
 
+use proc_macro2::TokenStream as TokenStream2;
 use syn::Item;
 
+mod alias_translator;
+mod constant_translator;
+mod enum_translator;
 mod function_translator;
+mod impl_translator;
 mod struct_translator;
+mod trait_translator;
+mod type_registry;
+mod type_translator;
 
 use crate::wrapper::*;
+use alias_translator::*;
+use constant_translator::*;
+use enum_translator::*;
 use function_translator::*;
+use impl_translator::*;
 use struct_translator::*;
+use trait_translator::*;
 
-pub(crate) fn translate(input: Item) -> Wrapper {
+/// `attr` is the argument of `#[ffi(..)]` on the item, only structs, functions and impl blocks
+/// accept it
+pub(crate) fn translate(attr: TokenStream2, input: Item) -> Wrapper {
+    if !attr.is_empty() && !matches!(input, Item::Struct(_) | Item::Fn(_) | Item::Impl(_)) {
+        panic!("Unsupported attribute: {attr}");
+    }
     match input {
-        Item::Struct(item_struct) => translate_struct(item_struct),
-        Item::Fn(item_fn) => translate_function(item_fn),
+        Item::Struct(item_struct) => translate_struct(item_struct, attr),
+        Item::Fn(item_fn) => translate_function(item_fn, attr),
+        Item::Enum(item_enum) => translate_enum(item_enum),
+        Item::Impl(item_impl) => translate_impl(item_impl, attr),
+        Item::Trait(item_trait) => translate_trait(item_trait),
+        Item::Type(item_type) => translate_type_alias(item_type),
+        Item::Const(item_const) => translate_const(item_const),
+        Item::Static(item_static) => translate_static(item_static),
         _ => panic!("Unsupported type: {:?}", input.type_id()),
     }
 }
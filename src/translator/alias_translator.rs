@@ -0,0 +1,35 @@
+use quote::quote;
+use syn::ItemType;
+
+use super::{
+    function_translator::check_custom_types,
+    type_registry::{register_type, RegisteredType},
+    type_translator::type_wrapper,
+};
+use crate::wrapper::*;
+
+/// Type alias, signatures and fields using it are translated as the aliased type, while the target
+/// languages get an alias of their own
+pub fn translate_type_alias(item_type: ItemType) -> Wrapper {
+    if !item_type.generics.params.is_empty() {
+        panic!("Generic type aliases are not supported");
+    }
+    let ty = &item_type.ty;
+    let wrapper_type = type_wrapper(ty);
+    check_custom_types(&wrapper_type);
+    register_type(
+        &item_type.ident,
+        RegisteredType::Alias {
+            ty: quote! {#ty}.to_string(),
+        },
+    );
+
+    Wrapper {
+        original_definition: quote! {#item_type},
+        parsed: ParsedWrapper::Alias(AliasWrapper {
+            name: item_type.ident.clone(),
+            wrapper_type,
+            original_item_type: item_type,
+        }),
+    }
+}
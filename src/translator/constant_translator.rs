@@ -0,0 +1,101 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Expr, Lit, UnOp};
+
+use super::type_translator::{reference_type_wrapper, type_wrapper};
+use crate::wrapper::*;
+use crate::EXPORTED_SYMBOLS_PREFIX;
+
+/// Immutable static, read like a constant
+pub fn translate_static(item_static: syn::ItemStatic) -> Wrapper {
+    if !matches!(item_static.mutability, syn::StaticMutability::None) {
+        panic!("Mutable statics are not supported: {}", item_static.ident);
+    }
+    translate_constant(
+        &item_static.ident,
+        &item_static.ty,
+        &item_static.expr,
+        quote! {#item_static},
+    )
+}
+
+pub fn translate_const(item_const: syn::ItemConst) -> Wrapper {
+    if !item_const.generics.params.is_empty() {
+        panic!("Generic constants are not supported: {}", item_const.ident);
+    }
+    translate_constant(
+        &item_const.ident,
+        &item_const.ty,
+        &item_const.expr,
+        quote! {#item_const},
+    )
+}
+
+/// `const` or `static` item of a primitive or string type. Literal values are copied to the target
+/// languages, other values are read once with a getter extern function.
+fn translate_constant(
+    name: &syn::Ident,
+    ty: &syn::Type,
+    expr: &Expr,
+    original_definition: TokenStream2,
+) -> Wrapper {
+    let wrapper_type = constant_type_wrapper(ty);
+    let literal = match wrapper_type {
+        // 128-bit values are passed as pairs of words, which have no literals
+        TypeWrapper::Int128(_) => None,
+        _ => constant_literal(expr),
+    };
+
+    Wrapper {
+        original_definition,
+        parsed: ParsedWrapper::Constant(ConstantWrapper {
+            name: name.clone(),
+            extern_function_name: format!("{EXPORTED_SYMBOLS_PREFIX}${name}"),
+            wrapper_type,
+            literal,
+        }),
+    }
+}
+
+/// References of constants are `'static`, so `&str` is exported as `&'static str`
+fn constant_type_wrapper(ty: &syn::Type) -> TypeWrapper {
+    let wrapper_type = match ty {
+        syn::Type::Reference(reference) => reference_type_wrapper(reference),
+        ty => type_wrapper(ty),
+    };
+    match wrapper_type {
+        TypeWrapper::Str => TypeWrapper::StaticStr,
+        TypeWrapper::Primitive(_)
+        | TypeWrapper::Int128(_)
+        | TypeWrapper::Char
+        | TypeWrapper::StaticStr => wrapper_type,
+        _ => panic!(
+            "Constants can have only primitive, `char` and `&str` types: {}",
+            quote! {#ty}
+        ),
+    }
+}
+
+/// Value of a literal expression, optionally negated
+fn constant_literal(expr: &Expr) -> Option<ConstantLiteral> {
+    match expr {
+        Expr::Lit(expr_lit) => match &expr_lit.lit {
+            Lit::Int(lit) => Some(ConstantLiteral::Int(lit.base10_digits().to_string())),
+            Lit::Float(lit) => Some(ConstantLiteral::Float(lit.base10_parse().ok()?)),
+            Lit::Bool(lit) => Some(ConstantLiteral::Bool(lit.value)),
+            Lit::Char(lit) => Some(ConstantLiteral::Char(lit.value())),
+            Lit::Str(lit) => Some(ConstantLiteral::Str(lit.value())),
+            _ => None,
+        },
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => {
+            match constant_literal(&unary.expr)? {
+                ConstantLiteral::Int(digits) => Some(ConstantLiteral::Int(format!("-{digits}"))),
+                ConstantLiteral::Float(value) => Some(ConstantLiteral::Float(-value)),
+                _ => None,
+            }
+        }
+        Expr::Group(group) => constant_literal(&group.expr),
+        Expr::Paren(paren) => constant_literal(&paren.expr),
+        _ => None,
+    }
+}
@@ -0,0 +1,149 @@
+use quote::{format_ident, quote, ToTokens};
+use syn::{Expr, ExprLit, ExprUnary, ItemEnum, Lit, UnOp};
+
+use super::{type_registry::*, type_translator::type_wrapper};
+use crate::{wrapper::*, EXPORTED_SYMBOLS_PREFIX};
+
+pub fn translate_enum(item_enum: ItemEnum) -> Wrapper {
+    if item_enum
+        .variants
+        .iter()
+        .all(|variant| matches!(variant.fields, syn::Fields::Unit))
+    {
+        translate_c_like_enum(item_enum)
+    } else {
+        translate_data_enum(item_enum)
+    }
+}
+
+fn translate_c_like_enum(item_enum: ItemEnum) -> Wrapper {
+    let enum_name = &item_enum.ident;
+    let repr = enum_repr(&item_enum);
+    register_type(
+        enum_name,
+        RegisteredType::Enum {
+            repr: repr.to_string(),
+        },
+    );
+
+    let variants = item_enum
+        .variants
+        .iter()
+        .map(|variant| EnumVariant {
+            name: variant.ident.clone(),
+            discriminant: variant
+                .discriminant
+                .as_ref()
+                .map(|(_, expr)| discriminant_literal(expr)),
+        })
+        .collect();
+
+    Wrapper {
+        original_definition: quote! {#item_enum},
+        parsed: ParsedWrapper::Enum(EnumWrapper {
+            name: enum_name.clone(),
+            repr,
+            variants,
+            original_item_enum: item_enum,
+        }),
+    }
+}
+
+fn translate_data_enum(item_enum: ItemEnum) -> Wrapper {
+    let enum_name = &item_enum.ident;
+    register_type(enum_name, RegisteredType::DataEnum);
+
+    let variants = item_enum
+        .variants
+        .iter()
+        .map(|variant| {
+            let variant_name = &variant.ident;
+            let fields = variant
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(index, field)| {
+                    let field_name = field
+                        .ident
+                        .clone()
+                        .unwrap_or_else(|| format_ident!("_{index}"));
+                    FieldWrapper {
+                        getter: Some(Getter {
+                            extern_fn_name: format!(
+                                "{EXPORTED_SYMBOLS_PREFIX}${enum_name}__{variant_name}__get_{field_name}"
+                            ),
+                            name: format_ident!("get_{field_name}"),
+                        }),
+                        setter: None,
+                        wrapper_type: type_wrapper(&field.ty),
+                        field_name,
+                    }
+                })
+                .collect();
+
+            DataEnumVariant {
+                name: variant_name.clone(),
+                named_fields: matches!(variant.fields, syn::Fields::Named(_)),
+                fields,
+                constructor_ext_fn_name: format!(
+                    "{EXPORTED_SYMBOLS_PREFIX}${enum_name}__{variant_name}__new"
+                ),
+            }
+        })
+        .collect();
+
+    Wrapper {
+        original_definition: quote! {#item_enum},
+        parsed: ParsedWrapper::DataEnum(DataEnumWrapper {
+            name: enum_name.clone(),
+            variants,
+            tag_ext_fn_name: format!("{EXPORTED_SYMBOLS_PREFIX}${enum_name}__tag"),
+            drop_ext_fn_name: format!("{EXPORTED_SYMBOLS_PREFIX}${enum_name}__drop"),
+            clone_ext_fn_name: format!("{EXPORTED_SYMBOLS_PREFIX}${enum_name}__clone"),
+            original_item_enum: item_enum,
+        }),
+    }
+}
+
+/// Integer type from `#[repr(...)]`, `i32` (C enum) if not specified
+fn enum_repr(item_enum: &ItemEnum) -> proc_macro2::Ident {
+    let mut repr = format_ident!("i32");
+
+    for attr in &item_enum.attrs {
+        if attr.path().is_ident("repr") {
+            let _ = attr.parse_nested_meta(|meta| {
+                if let Some(ident) = meta.path.get_ident() {
+                    match ident.to_string().as_str() {
+                        "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64"
+                        | "usize" => repr = ident.clone(),
+                        "i128" | "u128" => {
+                            panic!("C-like enums represented as `{ident}` are not supported")
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(())
+            });
+        }
+    }
+
+    repr
+}
+
+/// Discriminants are copied to the target languages, so only integer literals are accepted
+fn discriminant_literal(expr: &Expr) -> String {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit.base10_digits().to_string(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => format!("-{}", discriminant_literal(expr)),
+        _ => panic!(
+            "Only integer literal discriminants are supported: {}",
+            expr.to_token_stream()
+        ),
+    }
+}
@@ -1,89 +1,387 @@
 use std::ops::Deref;
 
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{FnArg, ItemFn};
+use syn::{
+    punctuated::Punctuated, FnArg, GenericArgument, Ident, ItemFn, PathArguments, Receiver,
+    Signature, Token,
+};
 
-use crate::{wrapper::*, EXPORTED_SYMBOLS_PREFIX};
+use super::{
+    type_registry::{registered_type, RegisteredType},
+    type_translator::{known_type_name, reference_type_wrapper, type_wrapper},
+};
+use crate::{
+    wrapper::{base::MAX_TUPLE_LEN, *},
+    EXPORTED_SYMBOLS_PREFIX,
+};
 
-pub fn translate_function(item_struct: ItemFn) -> Wrapper {
-    let fn_name = &item_struct.sig.ident;
-    let args_wrappers = item_struct
-        .sig
+pub fn translate_function(item_fn: ItemFn, attr: TokenStream2) -> Wrapper {
+    let cpp_expected = cpp_expected_attr(attr);
+    Wrapper {
+        parsed: ParsedWrapper::Function(function_wrapper(&item_fn.sig, None, cpp_expected)),
+        original_definition: quote! {#item_fn},
+    }
+}
+
+/// `cpp_expected` argument of `#[ffi(..)]`, errors of the item are returned in `hi_ffi::Expected`
+/// rather than thrown in C++. `cpp-expected` feature enables it for every item of the build.
+pub(super) fn cpp_expected_attr(attr: TokenStream2) -> bool {
+    let mut cpp_expected = cfg!(feature = "cpp-expected");
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("cpp_expected") {
+            cpp_expected = true;
+            return Ok(());
+        }
+        Err(meta.error("Unsupported attribute"))
+    });
+    syn::parse::Parser::parse2(parser, attr).unwrap_or_else(|error| panic!("{error}"));
+    cpp_expected
+}
+
+/// Function or, when `self_type` is given, a method or an associated function of `self_type`
+pub(super) fn function_wrapper(
+    sig: &Signature,
+    self_type: Option<&Ident>,
+    cpp_expected: bool,
+) -> FunctionWrapper {
+    let fn_name = &sig.ident;
+    let receiver = sig
+        .receiver()
+        .map(|receiver| receiver_wrapper(receiver, self_type));
+    let args_wrappers = sig
         .inputs
         .iter()
-        .map(map_arg)
+        .filter_map(|arg| match arg {
+            FnArg::Receiver(_) => None,
+            FnArg::Typed(pat_type) => Some(map_arg(pat_type, self_type)),
+        })
         .collect::<Vec<_>>();
 
-    let return_wrapper = return_wrapper(&item_struct.sig.output);
+    let (return_wrapper, error_wrapper) =
+        return_wrapper(&sig.output, self_type, receiver.is_some(), cpp_expected);
 
-    Wrapper {
-        original_definition: quote! {#item_struct},
-        parsed: ParsedWrapper::Function(FunctionWrapper {
-            name: fn_name.clone(),
-            extern_function_name: format!("{EXPORTED_SYMBOLS_PREFIX}${fn_name}"),
-            args_wrappers,
-            return_wrapper,
-        }),
+    let extern_function_name = match self_type {
+        Some(self_type) => format!("{EXPORTED_SYMBOLS_PREFIX}${self_type}__{fn_name}"),
+        None => format!("{EXPORTED_SYMBOLS_PREFIX}${fn_name}"),
+    };
+
+    FunctionWrapper {
+        name: fn_name.clone(),
+        extern_function_name,
+        self_type: self_type.cloned(),
+        receiver,
+        args_wrappers,
+        return_wrapper,
+        error_wrapper,
     }
 }
 
-fn return_wrapper(output: &syn::ReturnType) -> Option<FunctionReturnWrapper> {
+/// Whether the associated function returns `Self` or `Result<Self, E>`, so it is a constructor
+pub(super) fn returns_self(sig: &Signature, self_type: &Ident) -> bool {
+    let syn::ReturnType::Type(_, ty) = &sig.output else {
+        return false;
+    };
+    let ty = result_types(ty).map_or(ty.deref(), |(ok_type, _)| ok_type);
+    matches!(ty, syn::Type::Path(path) if path.path.is_ident("Self") || path.path.is_ident(self_type))
+}
+
+fn receiver_wrapper(receiver: &Receiver, self_type: Option<&Ident>) -> ReceiverWrapper {
+    if self_type.is_none() {
+        panic!("Receiver argument is only supported in methods");
+    }
+    if receiver.reference.is_none() {
+        panic!("Methods taking `self` by value are not supported: use `&self` or `&mut self`");
+    }
+    ReceiverWrapper {
+        mutable: receiver.mutability.is_some(),
+    }
+}
+
+fn return_wrapper(
+    output: &syn::ReturnType,
+    self_type: Option<&Ident>,
+    borrows_self: bool,
+    cpp_expected: bool,
+) -> (Option<FunctionReturnWrapper>, Option<ErrorWrapper>) {
     match output {
-        syn::ReturnType::Default => None,
-        syn::ReturnType::Type(_, ty) => {
-            if let syn::Type::Path(path) = ty.deref() {
-                if let Some(ident) = path.path.get_ident() {
-                    let wrapper_type = match ident.to_string().as_str() {
-                        "i8" | "i16" | "i32" | "i64" | "i128" | "u8" | "u16" | "u32" | "u64"
-                        | "u128" | "f32" | "f64" | "bool" => FunctionReturnWrapperType::Primitive,
-                        "String" => FunctionReturnWrapperType::String,
-                        _ => panic!("Unsupported type: {}", ident),
-                    };
-
-                    Some(FunctionReturnWrapper {
-                        wrapper_type,
-                        return_type: ty.deref().clone(),
-                    })
-                } else {
-                    panic!("No ident found in return type")
+        syn::ReturnType::Default => (None, None),
+        syn::ReturnType::Type(_, ty) => match result_types(ty) {
+            Some((ok_type, error_type)) => (
+                ok_return_wrapper(ok_type, self_type, borrows_self),
+                Some(error_wrapper(error_type, cpp_expected)),
+            ),
+            None => (ok_return_wrapper(ty, self_type, borrows_self), None),
+        },
+    }
+}
+
+/// Returned `&str` is either `'static` or, in methods, borrowed from `&self` with elided lifetime,
+/// which is copied as the object may be released before the string is used
+fn ok_return_wrapper(
+    ty: &syn::Type,
+    self_type: Option<&Ident>,
+    borrows_self: bool,
+) -> Option<FunctionReturnWrapper> {
+    match ty {
+        syn::Type::Tuple(tuple) if tuple.elems.is_empty() => None,
+        syn::Type::Reference(reference) => {
+            let wrapper_type = reference_type_wrapper(reference);
+            match wrapper_type {
+                TypeWrapper::Str if !(borrows_self && reference.lifetime.is_none()) => {
+                    panic!("Returned `&str` has to be `&'static str` or borrow from `&self`")
+                }
+                TypeWrapper::Bytes(_) => {
+                    panic!("Returning `&[u8]` is not supported, return `Vec<u8>` or `Box<[u8]>`")
+                }
+                _ => {}
+            }
+            Some(FunctionReturnWrapper { wrapper_type })
+        }
+        _ => Some(FunctionReturnWrapper {
+            wrapper_type: function_type_wrapper(ty, self_type),
+        }),
+    }
+}
+
+/// Errors of `#[ffi]` enum types carry their value, other errors only the message. C-like enums
+/// are passed as `i64`, so ones represented as `u64` or `usize` are not supported.
+fn error_wrapper(error_type: &syn::Type, cpp_expected: bool) -> ErrorWrapper {
+    let registered = match error_type {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .and_then(|segment| registered_type(segment.ident.to_string())),
+        _ => None,
+    };
+    let is_ffi_enum = match registered {
+        Some(RegisteredType::Enum { repr }) if repr == "u64" || repr == "usize" => {
+            panic!("Error enums represented as `{repr}` are not supported, their values don't fit `i64`")
+        }
+        Some(RegisteredType::Enum { .. } | RegisteredType::DataEnum) => true,
+        _ => false,
+    };
+
+    ErrorWrapper {
+        error_type: error_type.clone(),
+        wrapper_type: is_ffi_enum.then(|| type_wrapper(error_type)),
+        cpp_expected,
+    }
+}
+
+/// `T` and `E` types of `Result<T, E>`
+fn result_types(ty: &syn::Type) -> Option<(&syn::Type, &syn::Type)> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args)
+            if known_type_name(&path.path).as_deref() == Some("Result") =>
+        {
+            let mut types = args.args.iter();
+            match (types.next(), types.next()) {
+                (Some(GenericArgument::Type(ok_type)), Some(GenericArgument::Type(error_type))) => {
+                    Some((ok_type, error_type))
                 }
-            } else {
-                panic!("No path found in return type")
+                _ => panic!("Result has to specify both Ok and Err types"),
             }
         }
+        _ => None,
     }
 }
 
-fn map_arg(arg: &FnArg) -> FunctionArgWrapper {
-    match arg {
-        syn::FnArg::Receiver(_) => panic!("Receiver argument is not supported"),
-        syn::FnArg::Typed(pat_type) => {
-            let pat = &pat_type.pat;
-            let ty = &pat_type.ty;
-            let arg_name = match pat.deref() {
-                syn::Pat::Ident(ident) => ident.ident.clone(),
-                _ => panic!("Only simple argument names are supported"),
-            };
-            if let syn::Type::Path(path) = ty.deref() {
-                if let Some(ident) = path.path.get_ident() {
-                    let wrapper_type = match ident.to_string().as_str() {
-                        "i8" | "i16" | "i32" | "i64" | "i128" | "u8" | "u16" | "u32" | "u64"
-                        | "u128" | "f32" | "f64" | "bool" => FunctionArgWrapperType::Primitive,
-                        "String" => FunctionArgWrapperType::String,
-                        _ => panic!("Unsupported type: {}", ident),
-                    };
-
-                    FunctionArgWrapper {
-                        wrapper_type,
-                        arg_name,
-                        arg_type: ty.deref().clone(),
+fn map_arg(pat_type: &syn::PatType, self_type: Option<&Ident>) -> FunctionArgWrapper {
+    let arg_name = match pat_type.pat.deref() {
+        syn::Pat::Ident(ident) => ident.ident.clone(),
+        _ => panic!("Only simple argument names are supported"),
+    };
+
+    let wrapper_type = match pat_type.ty.deref() {
+        syn::Type::Reference(reference) => match reference_type_wrapper(reference) {
+            TypeWrapper::StaticStr => panic!("`&'static str` arguments are not supported"),
+            wrapper_type => wrapper_type,
+        },
+        ty => callback_type_wrapper(ty, self_type)
+            .or_else(|| trait_type_wrapper(ty))
+            .unwrap_or_else(|| function_type_wrapper(ty, self_type)),
+    };
+
+    FunctionArgWrapper {
+        wrapper_type,
+        arg_name,
+    }
+}
+
+/// `Self` of methods and associated functions refers to `self_type`
+pub(super) fn function_type_wrapper(ty: &syn::Type, self_type: Option<&Ident>) -> TypeWrapper {
+    if let syn::Type::Tuple(tuple) = ty {
+        return tuple_type_wrapper(tuple, self_type);
+    }
+    let wrapper_type = match self_type {
+        Some(self_type) => resolve_self(type_wrapper(ty), self_type),
+        None => type_wrapper(ty),
+    };
+    check_custom_types(&wrapper_type);
+    wrapper_type
+}
+
+/// `impl Fn(..)` or `Box<dyn Fn(..)>` closure of the target language, `FnMut` and `FnOnce` bounds
+/// are accepted as well. Results of callbacks are limited to values that don't need to be released
+/// by the target language.
+fn callback_type_wrapper(ty: &syn::Type, self_type: Option<&Ident>) -> Option<TypeWrapper> {
+    let (boxed, bounds) = trait_bounds(ty)?;
+    let signature = bounds.iter().find_map(|bound| {
+        let syn::TypeParamBound::Trait(bound) = bound else {
+            return None;
+        };
+        let segment = bound.path.segments.last()?;
+        match &segment.arguments {
+            PathArguments::Parenthesized(signature)
+                if ["Fn", "FnMut", "FnOnce"].contains(&segment.ident.to_string().as_str()) =>
+            {
+                Some(signature)
+            }
+            _ => None,
+        }
+    })?;
+
+    let args = signature.inputs.iter().map(|arg| match arg {
+        syn::Type::Reference(_) | syn::Type::Tuple(_) => {
+            panic!("Unsupported callback argument type: {}", quote! {#arg})
+        }
+        arg => function_type_wrapper(arg, self_type),
+    });
+    let output = match &signature.output {
+        syn::ReturnType::Default => None,
+        syn::ReturnType::Type(_, output) => match function_type_wrapper(output, self_type) {
+            output @ (TypeWrapper::Primitive(_)
+            | TypeWrapper::Int128(_)
+            | TypeWrapper::Char
+            | TypeWrapper::Enum { .. }) => Some(Box::new(output)),
+            _ => panic!("Callbacks can return only primitives, `char` and C-like enums"),
+        },
+    };
+
+    if has_bound(bounds, "Sync") {
+        panic!("`Sync` callbacks are not supported, closures of the target language can be `Send` only");
+    }
+
+    Some(TypeWrapper::Callback {
+        boxed,
+        send: has_bound(bounds, "Send"),
+        args: args.collect(),
+        output,
+    })
+}
+
+/// `Box<dyn Trait>` or `impl Trait` of an `#[ffi]` trait implemented in the target language,
+/// `Send` and `Sync` bounds are accepted next to it when the trait has them as supertraits
+fn trait_type_wrapper(ty: &syn::Type) -> Option<TypeWrapper> {
+    let (boxed, bounds) = trait_bounds(ty)?;
+    let (name, send, sync) = bounds.iter().find_map(|bound| {
+        let syn::TypeParamBound::Trait(bound) = bound else {
+            return None;
+        };
+        let name = &bound.path.segments.last()?.ident;
+        match registered_type(name.to_string()) {
+            Some(RegisteredType::Trait { send, sync }) => Some((
+                TypeName {
+                    name: name.clone(),
+                    path: bound.path.clone(),
+                },
+                send,
+                sync,
+            )),
+            _ => None,
+        }
+    })?;
+    for (bound, supported) in [("Send", send), ("Sync", sync)] {
+        if has_bound(bounds, bound) && !supported {
+            panic!("`{name}` has to have `{bound}` supertrait to be passed as `{bound}`");
+        }
+    }
+
+    Some(TypeWrapper::Trait { name, boxed })
+}
+
+/// Whether `bounds` include the trait `name`, like `Send`
+pub(super) fn has_bound(bounds: &Punctuated<syn::TypeParamBound, Token![+]>, name: &str) -> bool {
+    bounds.iter().any(|bound| {
+        matches!(bound, syn::TypeParamBound::Trait(bound)
+            if bound.path.segments.last().is_some_and(|segment| segment.ident == name))
+    })
+}
+
+/// Whether `ty` is `Box<dyn ..>` and its bounds or `impl ..` bounds
+fn trait_bounds(ty: &syn::Type) -> Option<(bool, &Punctuated<syn::TypeParamBound, Token![+]>)> {
+    match ty {
+        syn::Type::ImplTrait(impl_trait) => Some((false, &impl_trait.bounds)),
+        syn::Type::Path(path) => {
+            let segment = path.path.segments.last()?;
+            match &segment.arguments {
+                PathArguments::AngleBracketed(args)
+                    if known_type_name(&path.path).as_deref() == Some("Box") =>
+                {
+                    match args.args.first() {
+                        Some(GenericArgument::Type(syn::Type::TraitObject(object))) => {
+                            Some((true, &object.bounds))
+                        }
+                        _ => None,
                     }
-                } else {
-                    panic!("No ident found")
                 }
-            } else {
-                panic!("No path found")
+                _ => None,
             }
         }
+        _ => None,
+    }
+}
+
+/// Tuples are supported in function signatures only, as `FfiTuple{N}` structs are declared
+/// together with the functions using them
+fn tuple_type_wrapper(tuple: &syn::TypeTuple, self_type: Option<&Ident>) -> TypeWrapper {
+    if !(2..=MAX_TUPLE_LEN).contains(&tuple.elems.len()) {
+        panic!("Tuples have to have from 2 to {MAX_TUPLE_LEN} elements");
+    }
+    let items = tuple.elems.iter().map(|item| match item {
+        syn::Type::Tuple(_) => panic!("Nested tuples are not supported"),
+        item => function_type_wrapper(item, self_type),
+    });
+    TypeWrapper::Tuple(items.collect())
+}
+
+/// Custom types are passed as handles, so they have to be `#[ffi]` items declared earlier
+pub(super) fn check_custom_types(wrapper_type: &TypeWrapper) {
+    match wrapper_type {
+        TypeWrapper::Custom(ident) => match registered_type(ident.to_string()) {
+            Some(RegisteredType::Struct { .. } | RegisteredType::DataEnum) => {}
+            _ => panic!("Unsupported type: {ident}, `#[ffi]` items have to be declared before use"),
+        },
+        TypeWrapper::Vec(item) | TypeWrapper::Option(item) => check_custom_types(item),
+        TypeWrapper::Map { value, .. } | TypeWrapper::Array { item: value, .. } => {
+            check_custom_types(value)
+        }
+        _ => {}
+    }
+}
+
+fn resolve_self(wrapper_type: TypeWrapper, self_type: &Ident) -> TypeWrapper {
+    match wrapper_type {
+        TypeWrapper::Custom(ty) if ty.name == "Self" => {
+            TypeWrapper::Custom(self_type.clone().into())
+        }
+        TypeWrapper::Vec(item) => TypeWrapper::Vec(Box::new(resolve_self(*item, self_type))),
+        TypeWrapper::Option(item) => TypeWrapper::Option(Box::new(resolve_self(*item, self_type))),
+        TypeWrapper::Map { kind, key, value } => TypeWrapper::Map {
+            kind,
+            key,
+            value: Box::new(resolve_self(*value, self_type)),
+        },
+        TypeWrapper::Array { item, len } => TypeWrapper::Array {
+            item: Box::new(resolve_self(*item, self_type)),
+            len,
+        },
+        wrapper_type => wrapper_type,
     }
 }
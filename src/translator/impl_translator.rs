@@ -0,0 +1,132 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Ident, ImplItem, ImplItemFn, ItemImpl, ItemStruct, Signature};
+
+use super::{
+    function_translator::{cpp_expected_attr, function_wrapper, returns_self},
+    struct_translator::struct_wrapper,
+    type_registry::{registered_type, update_type, RegisteredType},
+};
+use crate::wrapper::*;
+
+/// Exports `pub` methods and constructors of the impl block. Bindings of the struct are
+/// regenerated, so they contain functions of all `#[ffi] impl` blocks translated so far.
+pub fn translate_impl(item_impl: ItemImpl, attr: TokenStream2) -> Wrapper {
+    let cpp_expected = cpp_expected_attr(attr);
+    if let Some((_, path, _)) = &item_impl.trait_ {
+        if !path.is_ident("Default") {
+            panic!("Only `Default` trait implementations are supported");
+        }
+    }
+    let self_ty = &item_impl.self_ty;
+    let self_type = match self_ty.as_ref() {
+        syn::Type::Path(path) if path.qself.is_none() => path.path.get_ident().cloned(),
+        _ => None,
+    }
+    .unwrap_or_else(|| {
+        panic!(
+            "Unsupported impl type: `{}`, `#[ffi] impl` blocks have to name the struct without a path or generic arguments",
+            quote! {#self_ty}
+        )
+    });
+
+    let Some(RegisteredType::Struct {
+        definition,
+        mut signatures,
+    }) = registered_type(self_type.to_string())
+    else {
+        panic!("`{self_type}` has to be declared as `#[ffi]` struct before its impl block");
+    };
+
+    let exported_functions = exported_functions(&item_impl, &self_type);
+    let functions = exported_functions
+        .iter()
+        .map(|sig| function_wrapper(sig, Some(&self_type), cpp_expected))
+        .collect::<Vec<_>>();
+    let block_signatures = exported_functions
+        .iter()
+        .map(|sig| (quote! {#sig}.to_string(), cpp_expected))
+        .collect::<Vec<_>>();
+
+    // functions of the earlier blocks are translated again from their signatures, the block itself
+    // may be among them when it is expanded again
+    signatures.retain(|signature| !block_signatures.contains(signature));
+    let item_struct: ItemStruct = syn::parse_str(&definition).expect("Invalid struct definition");
+    let mut struct_wrapper = struct_wrapper(item_struct);
+    let (constructors, methods): (Vec<_>, Vec<_>) = signatures
+        .iter()
+        .map(|(sig, cpp_expected)| {
+            let sig: Signature = syn::parse_str(sig).expect("Invalid function signature");
+            function_wrapper(&sig, Some(&self_type), *cpp_expected)
+        })
+        .chain(functions.iter().cloned())
+        .partition(FunctionWrapper::is_constructor);
+    struct_wrapper.constructors.extend(constructors);
+    struct_wrapper.methods.extend(methods);
+
+    signatures.extend(block_signatures);
+    update_type(
+        &self_type,
+        RegisteredType::Struct {
+            definition,
+            signatures,
+        },
+    );
+
+    Wrapper {
+        original_definition: quote! {#item_impl},
+        parsed: ParsedWrapper::Impl(Box::new(ImplWrapper {
+            struct_wrapper,
+            functions,
+            original_item_impl: item_impl,
+        })),
+    }
+}
+
+/// Signatures of public methods taking `&self` or `&mut self` and constructors, which are public
+/// associated functions returning `Self` or ones marked with `#[ffi(constructor)]`. Other
+/// functions, including methods taking `self` by value, are left for Rust only and aren't
+/// translated.
+fn exported_functions<'a>(item_impl: &'a ItemImpl, self_type: &Ident) -> Vec<&'a Signature> {
+    // items of trait implementations are public
+    let is_trait_impl = item_impl.trait_.is_some();
+    item_impl
+        .items
+        .iter()
+        .filter_map(|item| {
+            let ImplItem::Fn(function) = item else {
+                return None;
+            };
+            let sig = &function.sig;
+            if is_constructor_attr(function) {
+                if sig.receiver().is_some() || !returns_self(sig, self_type) {
+                    panic!("Constructor `{}` has to return `Self`", sig.ident);
+                }
+                return Some(sig);
+            }
+            let is_public = is_trait_impl || matches!(function.vis, syn::Visibility::Public(_));
+            let is_exported = is_public
+                && match sig.receiver() {
+                    Some(receiver) => receiver.reference.is_some(),
+                    None => returns_self(sig, self_type),
+                };
+            is_exported.then_some(sig)
+        })
+        .collect()
+}
+
+fn is_constructor_attr(function: &ImplItemFn) -> bool {
+    let mut constructor = false;
+    function.attrs.iter().for_each(|attr| {
+        if attr.path().is_ident("ffi") {
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("constructor") {
+                    constructor = true;
+                    return Ok(());
+                }
+                Err(meta.error("Unsupported attribute"))
+            });
+        }
+    });
+    constructor
+}
@@ -1,33 +1,205 @@
-use core::panic;
 use std::fmt::Display;
 
+use proc_macro2::{Ident, TokenStream as TokenStream2};
 use quote::{format_ident, quote};
-use syn::ItemStruct;
+use syn::{GenericArgument, GenericParam, ItemStruct, PathArguments};
 
+use super::{
+    type_registry::{register_type, RegisteredType},
+    type_translator::type_wrapper,
+};
 use crate::{wrapper::*, EXPORTED_SYMBOLS_PREFIX};
 
-pub fn translate_struct(item_struct: ItemStruct) -> Wrapper {
-    let class_name = &item_struct.ident;
+pub fn translate_struct(item_struct: ItemStruct, attr: TokenStream2) -> Wrapper {
+    let instances = instances_attr(attr);
+    if !item_struct.generics.params.is_empty() {
+        return translate_generic_struct(item_struct, instances);
+    }
+    if !instances.is_empty() {
+        panic!("Only generic structs can be instantiated");
+    }
+    register_type(
+        &item_struct.ident,
+        RegisteredType::Struct {
+            definition: quote! {#item_struct}.to_string(),
+            signatures: Vec::new(),
+        },
+    );
 
     Wrapper {
         original_definition: quote! {#item_struct},
-        parsed: ParsedWrapper::Struct(StructWrapper {
-            name: class_name.clone(),
-            fields: fields_wrappers(&item_struct),
-            default_constructor: default_constructor(&item_struct),
-            drop_ext_fn_name: format!("{EXPORTED_SYMBOLS_PREFIX}${class_name}__drop"),
-            clone_ext_fn_name: format!("{EXPORTED_SYMBOLS_PREFIX}${class_name}__clone"),
+        parsed: ParsedWrapper::Struct(struct_wrapper(item_struct)),
+    }
+}
+
+/// Every instantiation is exported as a type alias named in `#[ffi(instantiate(..))]`. It is
+/// registered as a struct, so methods are exported by `#[ffi] impl` blocks of the alias, and
+/// signatures can use either the alias or the instantiated type.
+fn translate_generic_struct(
+    item_struct: ItemStruct,
+    instances: Vec<(Ident, syn::Type)>,
+) -> Wrapper {
+    let struct_name = &item_struct.ident;
+    if instances.is_empty() {
+        panic!(
+            "Generic struct `{struct_name}` has to be instantiated with `#[ffi(instantiate(Name = {struct_name}<..>))]`"
+        );
+    }
+    let params = item_struct
+        .generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Type(param) => param.ident.clone(),
+            _ => panic!("Only type parameters are supported in generic structs"),
+        })
+        .collect::<Vec<_>>();
+
+    let instances = instances
+        .into_iter()
+        .map(|(name, instance_type)| {
+            let args = instance_args(struct_name, &instance_type, params.len());
+            let substitutions = params.iter().cloned().zip(args).collect::<Vec<_>>();
+
+            let mut instance_struct = item_struct.clone();
+            instance_struct.ident = name.clone();
+            instance_struct.generics = Default::default();
+            instance_struct
+                .fields
+                .iter_mut()
+                .for_each(|field| substitute(&mut field.ty, &substitutions));
+
+            register_type(
+                &name,
+                RegisteredType::Struct {
+                    definition: quote! {#instance_struct}.to_string(),
+                    signatures: Vec::new(),
+                },
+            );
+            register_type(
+                quote! {#instance_type},
+                RegisteredType::Instance {
+                    name: name.to_string(),
+                },
+            );
+
+            StructInstance {
+                instance_type,
+                struct_wrapper: struct_wrapper(instance_struct),
+            }
+        })
+        .collect();
+
+    Wrapper {
+        original_definition: quote! {#item_struct},
+        parsed: ParsedWrapper::GenericStruct(GenericStructWrapper {
+            instances,
             original_item_struct: item_struct,
         }),
     }
 }
 
+/// `Name = Struct<Types>` pairs of `instantiate(..)` attribute
+fn instances_attr(attr: TokenStream2) -> Vec<(Ident, syn::Type)> {
+    let mut instances = Vec::new();
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("instantiate") {
+            return meta.parse_nested_meta(|instance| {
+                let name = instance.path.require_ident()?.clone();
+                let instance_type: syn::Type = instance.value()?.parse()?;
+                instances.push((name, instance_type));
+                Ok(())
+            });
+        }
+        Err(meta.error("Unsupported attribute"))
+    });
+    syn::parse::Parser::parse2(parser, attr).unwrap_or_else(|error| panic!("{error}"));
+    instances
+}
+
+/// Types of `instance_type` generic arguments, it has to instantiate `struct_name`
+fn instance_args(struct_name: &Ident, instance_type: &syn::Type, count: usize) -> Vec<syn::Type> {
+    let syn::Type::Path(path) = instance_type else {
+        panic!("Unsupported instance type: {}", quote! {#instance_type});
+    };
+    let segment = path.path.segments.last().expect("No path segment found");
+    let args = match &segment.arguments {
+        PathArguments::AngleBracketed(args) if segment.ident == *struct_name => &args.args,
+        _ => panic!(
+            "`{}` is not an instance of `{struct_name}`",
+            quote! {#instance_type}
+        ),
+    };
+    let args = args
+        .iter()
+        .map(|arg| match arg {
+            GenericArgument::Type(arg) => arg.clone(),
+            _ => panic!("Only type arguments are supported: {}", quote! {#arg}),
+        })
+        .collect::<Vec<_>>();
+    if args.len() != count {
+        panic!("`{struct_name}` has {count} type parameters");
+    }
+    args
+}
+
+/// Replaces generic parameters used in `ty` with the instantiated types
+fn substitute(ty: &mut syn::Type, substitutions: &[(Ident, syn::Type)]) {
+    if let syn::Type::Path(path) = ty {
+        let substitution = path.path.get_ident().and_then(|ident| {
+            substitutions
+                .iter()
+                .find(|(param, _)| param == ident)
+                .map(|(_, arg)| arg.clone())
+        });
+        if let Some(substitution) = substitution {
+            *ty = substitution;
+            return;
+        }
+    }
+    match ty {
+        syn::Type::Path(path) => path.path.segments.iter_mut().for_each(|segment| {
+            if let PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                args.args.iter_mut().for_each(|arg| {
+                    if let GenericArgument::Type(arg) = arg {
+                        substitute(arg, substitutions);
+                    }
+                });
+            }
+        }),
+        syn::Type::Reference(reference) => substitute(&mut reference.elem, substitutions),
+        syn::Type::Slice(slice) => substitute(&mut slice.elem, substitutions),
+        syn::Type::Array(array) => substitute(&mut array.elem, substitutions),
+        syn::Type::Tuple(tuple) => tuple
+            .elems
+            .iter_mut()
+            .for_each(|elem| substitute(elem, substitutions)),
+        _ => {}
+    }
+}
+
+/// Struct without methods and custom constructors, they are added by `#[ffi] impl` blocks
+pub(super) fn struct_wrapper(item_struct: ItemStruct) -> StructWrapper {
+    let class_name = &item_struct.ident;
+
+    StructWrapper {
+        name: class_name.clone(),
+        fields: fields_wrappers(&item_struct),
+        constructors: default_constructor(&item_struct).into_iter().collect(),
+        methods: Vec::new(),
+        drop_ext_fn_name: format!("{EXPORTED_SYMBOLS_PREFIX}${class_name}__drop"),
+        clone_ext_fn_name: format!("{EXPORTED_SYMBOLS_PREFIX}${class_name}__clone"),
+        original_item_struct: item_struct,
+    }
+}
+
+/// Fields without a getter or a setter are not translated, so they can use any types
 fn fields_wrappers(item_struct: &ItemStruct) -> Vec<FieldWrapper> {
     let class_name = &item_struct.ident;
     item_struct
         .fields
         .iter()
-        .map(|field| {
+        .filter_map(|field| {
             let is_public = matches!(field.vis, syn::Visibility::Public(_));
 
             let field_name = field.ident.clone().expect("No ident found for a field");
@@ -35,29 +207,16 @@ fn fields_wrappers(item_struct: &ItemStruct) -> Vec<FieldWrapper> {
 
             let getter = generate_getter(&field_attributes, class_name, &field_name, is_public);
             let setter = generate_setter(&field_attributes, class_name, &field_name, is_public);
-
-            if let syn::Type::Path(path) = &field.ty {
-                if let Some(ident) = path.path.get_ident() {
-                    let wrapper_type = match ident.to_string().as_str() {
-                        "i8" | "i16" | "i32" | "i64" | "i128" | "u8" | "u16" | "u32" | "u64"
-                        | "u128" | "f32" | "f64" | "bool" => FieldWrapperType::Primitive,
-                        "String" => FieldWrapperType::String,
-                        _custom_type => FieldWrapperType::Custom,
-                    };
-
-                    FieldWrapper {
-                        field_name,
-                        field_type: field.ty.clone(),
-                        getter,
-                        wrapper_type,
-                        setter,
-                    }
-                } else {
-                    panic!("No ident found")
-                }
-            } else {
-                panic!("No path found")
+            if getter.is_none() && setter.is_none() {
+                return None;
             }
+
+            Some(FieldWrapper {
+                field_name,
+                getter,
+                wrapper_type: type_wrapper(&field.ty),
+                setter,
+            })
         })
         .collect()
 }
@@ -132,7 +291,9 @@ fn extract_field_attributes(field: &syn::Field) -> FieldAttributes {
     }
 }
 
-fn default_constructor(item_struct: &ItemStruct) -> Option<DefaultConstructor> {
+/// `Default::default` of `#[derive(Default)]`, manual implementations are exported by
+/// `#[ffi] impl Default` blocks
+fn default_constructor(item_struct: &ItemStruct) -> Option<FunctionWrapper> {
     let mut default_constructor = None;
 
     let class_name = &item_struct.ident;
@@ -141,12 +302,18 @@ fn default_constructor(item_struct: &ItemStruct) -> Option<DefaultConstructor> {
         if attr.path().is_ident("derive") {
             let _ = attr.parse_nested_meta(|meta| {
                 if meta.path.is_ident("Default") {
-                    default_constructor = Some(DefaultConstructor {
-                        extern_fn_name: format!(
-                            "{EXPORTED_SYMBOLS_PREFIX}${class_name}__default",
-                            class_name = class_name
+                    default_constructor = Some(FunctionWrapper {
+                        name: format_ident!("default"),
+                        extern_function_name: format!(
+                            "{EXPORTED_SYMBOLS_PREFIX}${class_name}__default"
                         ),
-                        constructor_name: format_ident!("{class_name}__default"),
+                        self_type: Some(class_name.clone()),
+                        receiver: None,
+                        args_wrappers: Vec::new(),
+                        return_wrapper: Some(FunctionReturnWrapper {
+                            wrapper_type: TypeWrapper::Custom(class_name.clone().into()),
+                        }),
+                        error_wrapper: None,
                     });
                     return Ok(());
                 }
@@ -0,0 +1,100 @@
+use std::ops::Deref;
+
+use quote::quote;
+use syn::{FnArg, ItemTrait, Signature, TraitItem};
+
+use super::{
+    function_translator::{function_type_wrapper, has_bound},
+    type_registry::{register_type, RegisteredType},
+};
+use crate::wrapper::*;
+
+/// Trait implemented in the target language. Every method is called through a vtable, so the
+/// trait can't be generic and its methods have to take `&self` or `&mut self`. Implementations
+/// are `Send` or `Sync` only when the trait has these supertraits.
+pub fn translate_trait(item_trait: ItemTrait) -> Wrapper {
+    if !item_trait.generics.params.is_empty() {
+        panic!("Generic traits are not supported");
+    }
+    let (send, sync) = (
+        has_bound(&item_trait.supertraits, "Send"),
+        has_bound(&item_trait.supertraits, "Sync"),
+    );
+    register_type(&item_trait.ident, RegisteredType::Trait { send, sync });
+
+    let methods = item_trait
+        .items
+        .iter()
+        .map(|item| match item {
+            TraitItem::Fn(method) => method_wrapper(&method.sig),
+            _ => panic!("Only methods are supported in `#[ffi]` traits"),
+        })
+        .collect();
+
+    Wrapper {
+        original_definition: quote! {#item_trait},
+        parsed: ParsedWrapper::Trait(TraitWrapper {
+            name: item_trait.ident.clone(),
+            methods,
+            send,
+            sync,
+            original_item_trait: item_trait,
+        }),
+    }
+}
+
+/// Arguments are passed from Rust and the result is passed back, so references, tuples and
+/// closures are not supported
+fn method_wrapper(sig: &Signature) -> TraitMethodWrapper {
+    let method_name = &sig.ident;
+    if !sig.generics.params.is_empty() {
+        panic!("Generic trait methods are not supported: {method_name}");
+    }
+    let mutable = match sig.receiver() {
+        Some(receiver) if receiver.reference.is_some() => receiver.mutability.is_some(),
+        _ => panic!("Trait methods have to take `&self` or `&mut self`: {method_name}"),
+    };
+
+    let args_wrappers = sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Receiver(_) => None,
+            FnArg::Typed(pat_type) => Some(pat_type),
+        })
+        .map(|pat_type| {
+            let arg_name = match pat_type.pat.deref() {
+                syn::Pat::Ident(ident) => ident.ident.clone(),
+                _ => panic!("Only simple argument names are supported"),
+            };
+            FunctionArgWrapper {
+                wrapper_type: method_type_wrapper(&pat_type.ty),
+                arg_name,
+            }
+        })
+        .collect();
+
+    let return_wrapper = match &sig.output {
+        syn::ReturnType::Default => None,
+        syn::ReturnType::Type(_, ty) => Some(FunctionReturnWrapper {
+            wrapper_type: method_type_wrapper(ty),
+        }),
+    };
+
+    TraitMethodWrapper {
+        name: method_name.clone(),
+        mutable,
+        args_wrappers,
+        return_wrapper,
+        signature: sig.clone(),
+    }
+}
+
+fn method_type_wrapper(ty: &syn::Type) -> TypeWrapper {
+    match ty {
+        syn::Type::Reference(_) | syn::Type::Tuple(_) | syn::Type::ImplTrait(_) => {
+            panic!("Unsupported trait method type: {}", quote! {#ty})
+        }
+        ty => function_type_wrapper(ty, None),
+    }
+}
@@ -0,0 +1,81 @@
+use std::{collections::BTreeMap, sync::Mutex};
+
+/// Types that were already translated by the macro and need special handling when used by other
+/// items. Macro invocations within a crate run in a single process, so an item has to be declared
+/// before it is used in signatures or fields. Types are keyed by their crate, as a long-lived
+/// process, like the proc-macro server of rust-analyzer, expands items of many crates.
+static TYPE_REGISTRY: Mutex<BTreeMap<(String, String), Registration>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone)]
+pub(crate) enum RegisteredType {
+    /// C-like enum passed as its integer representation
+    Enum { repr: String },
+    /// Enum with data-carrying variants passed as an opaque handle
+    DataEnum,
+    /// Struct passed as an opaque handle. Its definition and signatures of the functions exported
+    /// by `#[ffi] impl` blocks are kept as source, so the bindings can be regenerated once methods
+    /// are added. Signatures are paired with `cpp_expected` argument of their block.
+    Struct {
+        definition: String,
+        signatures: Vec<(String, bool)>,
+    },
+    /// Instantiation of a generic struct, like `Page < User >`, exported as the type alias `name`
+    Instance { name: String },
+    /// Trait implemented in the target language and passed as `Box<dyn Trait>` or `impl Trait`,
+    /// its implementations are `Send` or `Sync` only when the trait has these supertraits
+    Trait { send: bool, sync: bool },
+    /// Type alias translated as the aliased type `ty`
+    Alias { ty: String },
+}
+
+/// Registered type with the source file of its item
+struct Registration {
+    file: String,
+    registered_type: RegisteredType,
+}
+
+/// Names are exported to the target languages without their modules, so types of a crate have to
+/// have unique names. A type registered again from the same file is the same item expanded again,
+/// e.g. after it was edited in the IDE.
+pub(crate) fn register_type(name: impl ToString, registered_type: RegisteredType) {
+    let name = name.to_string();
+    let file = proc_macro::Span::call_site().file();
+    let mut registry = TYPE_REGISTRY.lock().expect("Type registry poisoned");
+    let key = (crate_name(), name);
+    if let Some(registration) = registry.get(&key) {
+        if registration.file != file {
+            panic!(
+                "`{}` is already declared as `#[ffi]` item in {}, `#[ffi]` items have to have unique names",
+                key.1, registration.file
+            );
+        }
+    }
+    registry.insert(
+        key,
+        Registration {
+            file,
+            registered_type,
+        },
+    );
+}
+
+/// Replaces the registered type with its new version, like a struct with more `#[ffi] impl` blocks
+pub(crate) fn update_type(name: impl ToString, registered_type: RegisteredType) {
+    let mut registry = TYPE_REGISTRY.lock().expect("Type registry poisoned");
+    if let Some(registration) = registry.get_mut(&(crate_name(), name.to_string())) {
+        registration.registered_type = registered_type;
+    }
+}
+
+pub(crate) fn registered_type(name: impl AsRef<str>) -> Option<RegisteredType> {
+    TYPE_REGISTRY
+        .lock()
+        .expect("Type registry poisoned")
+        .get(&(crate_name(), name.as_ref().to_string()))
+        .map(|registration| registration.registered_type.clone())
+}
+
+/// Crate being compiled, Cargo sets its name for the compiler and the macros it runs
+fn crate_name() -> String {
+    std::env::var("CARGO_CRATE_NAME").unwrap_or_default()
+}
@@ -0,0 +1,260 @@
+use quote::{format_ident, quote};
+use syn::{AngleBracketedGenericArguments, GenericArgument, PathArguments};
+
+use super::type_registry::*;
+use crate::wrapper::base::C_PRIMITIVE_TYPES;
+use crate::wrapper::*;
+
+/// Modules of the std types known by their names, these types are used either unqualified or
+/// qualified with one of their modules
+const STD_MODULES: [(&str, &[&str]); 7] = [
+    ("String", &["std::string", "alloc::string"]),
+    ("Vec", &["std::vec", "alloc::vec"]),
+    (
+        "HashMap",
+        &["std::collections", "std::collections::hash_map"],
+    ),
+    (
+        "BTreeMap",
+        &[
+            "std::collections",
+            "std::collections::btree_map",
+            "alloc::collections",
+            "alloc::collections::btree_map",
+        ],
+    ),
+    ("Box", &["std::boxed", "alloc::boxed"]),
+    ("Option", &["std::option", "core::option"]),
+    ("Result", &["std::result", "core::result"]),
+];
+
+const PRIMITIVE_MODULES: [&str; 2] = ["std::primitive", "core::primitive"];
+
+/// Name of the type `path` refers to when it is unqualified or qualified with a module of the std
+/// type of that name, other qualified paths refer to `#[ffi]` items
+pub(super) fn known_type_name(path: &syn::Path) -> Option<String> {
+    let segment = path.segments.last()?;
+    let name = segment.ident.to_string();
+    if path.leading_colon.is_none() && path.segments.len() == 1 {
+        return Some(name);
+    }
+    let module = path
+        .segments
+        .iter()
+        .take(path.segments.len() - 1)
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::");
+    let is_std = PRIMITIVE_MODULES.contains(&module.as_str())
+        || STD_MODULES
+            .iter()
+            .any(|(std_name, modules)| *std_name == name && modules.contains(&module.as_str()));
+    is_std.then_some(name)
+}
+
+pub(super) fn type_wrapper(ty: &syn::Type) -> TypeWrapper {
+    if let syn::Type::Array(array) = ty {
+        return array_type_wrapper(array);
+    }
+    if let syn::Type::Path(path) = ty {
+        let segment = path.path.segments.last().expect("No path segment found");
+        let ident = &segment.ident;
+        match (&segment.arguments, known_type_name(&path.path).as_deref()) {
+            (PathArguments::None, Some(name))
+                if name == "bool"
+                    || C_PRIMITIVE_TYPES
+                        .iter()
+                        .any(|(rust_type, _)| *rust_type == name) =>
+            {
+                TypeWrapper::Primitive(ident.clone())
+            }
+            (PathArguments::None, Some("i128" | "u128")) => TypeWrapper::Int128(ident.clone()),
+            (PathArguments::None, Some("char")) => TypeWrapper::Char,
+            (PathArguments::None, Some("String")) => TypeWrapper::String,
+            (PathArguments::None, _) => custom_type_wrapper(&path.path),
+            (PathArguments::AngleBracketed(args), Some("Vec")) => match item_type_wrapper(args) {
+                TypeWrapper::Primitive(item) if item == "u8" => TypeWrapper::Bytes(BytesType::Vec),
+                item => TypeWrapper::Vec(Box::new(item)),
+            },
+            (PathArguments::AngleBracketed(args), Some("HashMap")) => {
+                map_type_wrapper(MapKind::Hash, args)
+            }
+            (PathArguments::AngleBracketed(args), Some("BTreeMap")) => {
+                map_type_wrapper(MapKind::BTree, args)
+            }
+            (PathArguments::AngleBracketed(args), Some("Box"))
+                if is_u8_slice(args.args.first()) =>
+            {
+                TypeWrapper::Bytes(BytesType::Boxed)
+            }
+            (PathArguments::AngleBracketed(args), Some("Option")) => {
+                TypeWrapper::Option(Box::new(item_type_wrapper(args)))
+            }
+            // instances are registered without the path of the generic struct
+            (PathArguments::AngleBracketed(_), _) => {
+                match registered_type(quote! {#segment}.to_string()) {
+                    Some(RegisteredType::Instance { name }) => {
+                        let mut path = path.path.clone();
+                        let segment = path.segments.last_mut().expect("No path segment found");
+                        segment.ident = format_ident!("{name}");
+                        segment.arguments = PathArguments::None;
+                        TypeWrapper::Custom(TypeName {
+                            name: segment.ident.clone(),
+                            path,
+                        })
+                    }
+                    _ => panic!("Unsupported type: {}", quote! {#ty}),
+                }
+            }
+            _ => panic!("Unsupported type: {ident}"),
+        }
+    } else {
+        panic!("Unsupported type: {}", quote! {#ty})
+    }
+}
+
+/// `#[ffi]` item, qualified paths are resolved by the item name
+fn custom_type_wrapper(path: &syn::Path) -> TypeWrapper {
+    let ident = &path.segments.last().expect("No path segment found").ident;
+    let name = TypeName {
+        name: ident.clone(),
+        path: path.clone(),
+    };
+    match registered_type(ident.to_string()) {
+        Some(RegisteredType::Enum { repr }) => TypeWrapper::Enum {
+            name,
+            repr: format_ident!("{repr}"),
+        },
+        // `Self` is resolved by the function translator
+        Some(RegisteredType::DataEnum | RegisteredType::Struct { .. }) => TypeWrapper::Custom(name),
+        None if ident == "Self" => TypeWrapper::Custom(name),
+        None => panic!("Unsupported type: {ident}, `#[ffi]` items have to be declared before use"),
+        Some(RegisteredType::Instance { .. }) => {
+            unreachable!("Instances are registered with generic arguments")
+        }
+        Some(RegisteredType::Trait { .. }) => {
+            panic!("`{ident}` is a trait, pass it as `Box<dyn {ident}>` or `impl {ident}`")
+        }
+        // the aliased type only chooses the wrapper, as its path is valid in the module of the alias,
+        // while Rust glue code refers to the alias itself
+        Some(RegisteredType::Alias { ty }) => {
+            match type_wrapper(&syn::parse_str(&ty).expect("Invalid alias type")) {
+                TypeWrapper::Custom(target) => TypeWrapper::Custom(TypeName {
+                    path: path.clone(),
+                    ..target
+                }),
+                TypeWrapper::Enum { name, repr } => TypeWrapper::Enum {
+                    name: TypeName {
+                        path: path.clone(),
+                        ..name
+                    },
+                    repr,
+                },
+                wrapper => wrapper,
+            }
+        }
+    }
+}
+
+/// Arrays of values passed by value or as handles, their length has to be an integer literal
+fn array_type_wrapper(array: &syn::TypeArray) -> TypeWrapper {
+    let item = type_wrapper(&array.elem);
+    if !matches!(
+        item,
+        TypeWrapper::Primitive(_)
+            | TypeWrapper::Int128(_)
+            | TypeWrapper::Char
+            | TypeWrapper::Enum { .. }
+            | TypeWrapper::Custom(_)
+    ) {
+        panic!("Unsupported array item type: {item:?}");
+    }
+    let len = match &array.len {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(len),
+            ..
+        }) => len.base10_parse().expect("Invalid array length"),
+        len => panic!(
+            "Array length has to be an integer literal: {}",
+            quote! {#len}
+        ),
+    };
+    TypeWrapper::Array {
+        item: Box::new(item),
+        len,
+    }
+}
+
+/// `&str` borrowed from the caller or `&'static str` and `&[u8]` borrowed from the caller,
+/// references are supported in functions only
+pub(super) fn reference_type_wrapper(reference: &syn::TypeReference) -> TypeWrapper {
+    let is_static = matches!(&reference.lifetime, Some(lifetime) if lifetime.ident == "static");
+    match reference.elem.as_ref() {
+        _ if reference.mutability.is_some() => {
+            panic!("Unsupported reference type: {}", quote! {#reference})
+        }
+        syn::Type::Path(path) if is_str(&path.path) && is_static => TypeWrapper::StaticStr,
+        syn::Type::Path(path) if is_str(&path.path) => TypeWrapper::Str,
+        syn::Type::Slice(slice) if is_u8(&slice.elem) && !is_static => {
+            TypeWrapper::Bytes(BytesType::Slice)
+        }
+        _ => panic!("Unsupported reference type: {}", quote! {#reference}),
+    }
+}
+
+/// Whether `arg` of `Box<arg>` is `[u8]`
+fn is_u8_slice(arg: Option<&GenericArgument>) -> bool {
+    matches!(arg, Some(GenericArgument::Type(syn::Type::Slice(slice))) if is_u8(&slice.elem))
+}
+
+fn is_u8(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(path) if known_type_name(&path.path).as_deref() == Some("u8"))
+}
+
+fn is_str(path: &syn::Path) -> bool {
+    known_type_name(path).as_deref() == Some("str")
+}
+
+/// Item of a container, nested containers are not supported
+fn item_type_wrapper(args: &AngleBracketedGenericArguments) -> TypeWrapper {
+    match args.args.first() {
+        Some(GenericArgument::Type(item)) => container_item(item),
+        _ => panic!("No item type found"),
+    }
+}
+
+/// Keys are limited to types that are hashable and ordered in the target languages
+fn map_type_wrapper(kind: MapKind, args: &AngleBracketedGenericArguments) -> TypeWrapper {
+    let mut types = args.args.iter();
+    let (key, value) = match (types.next(), types.next()) {
+        (Some(GenericArgument::Type(key)), Some(GenericArgument::Type(value))) => {
+            (container_item(key), container_item(value))
+        }
+        _ => panic!("Map has to specify both key and value types"),
+    };
+    match key {
+        TypeWrapper::String
+        | TypeWrapper::Int128(_)
+        | TypeWrapper::Char
+        | TypeWrapper::Enum { .. } => {}
+        TypeWrapper::Primitive(ref ty) if ty != "f32" && ty != "f64" => {}
+        _ => panic!("Unsupported map key type: {key:?}"),
+    }
+    TypeWrapper::Map {
+        kind,
+        key: Box::new(key),
+        value: Box::new(value),
+    }
+}
+
+fn container_item(ty: &syn::Type) -> TypeWrapper {
+    let item = type_wrapper(ty);
+    match item {
+        TypeWrapper::Vec(_)
+        | TypeWrapper::Option(_)
+        | TypeWrapper::Bytes(_)
+        | TypeWrapper::Map { .. }
+        | TypeWrapper::Array { .. } => panic!("Unsupported item type: {item:?}"),
+        _ => item,
+    }
+}
@@ -4,20 +4,34 @@ use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 
+pub mod alias_wrapper;
 pub mod base;
+pub mod constant_wrapper;
 #[cfg(feature = "cpp")]
 mod cpp;
+pub mod data_enum_wrapper;
+pub mod enum_wrapper;
 pub mod function_wrapper;
+pub mod impl_wrapper;
 pub mod struct_wrapper;
 #[cfg(feature = "swift")]
 mod swift;
+pub mod trait_wrapper;
+pub mod type_wrapper;
 
+pub use alias_wrapper::*;
+pub use constant_wrapper::*;
 #[cfg(feature = "cpp")]
 pub use cpp::*;
+pub use data_enum_wrapper::*;
+pub use enum_wrapper::*;
 pub use function_wrapper::*;
+pub use impl_wrapper::*;
 pub use struct_wrapper::*;
 #[cfg(feature = "swift")]
 pub use swift::*;
+pub use trait_wrapper::*;
+pub use type_wrapper::*;
 
 #[derive(Debug)]
 pub struct Wrapper {
@@ -29,7 +43,16 @@ impl Wrapper {
     pub fn name(&self) -> String {
         match &self.parsed {
             ParsedWrapper::Struct(struct_wrapper) => struct_wrapper.name.to_string(),
+            ParsedWrapper::GenericStruct(generic_wrapper) => {
+                generic_wrapper.original_item_struct.ident.to_string()
+            }
             ParsedWrapper::Function(function_wrapper) => function_wrapper.name.to_string(),
+            ParsedWrapper::Enum(enum_wrapper) => enum_wrapper.name.to_string(),
+            ParsedWrapper::DataEnum(enum_wrapper) => enum_wrapper.name.to_string(),
+            ParsedWrapper::Impl(impl_wrapper) => impl_wrapper.struct_wrapper.name.to_string(),
+            ParsedWrapper::Trait(trait_wrapper) => trait_wrapper.name.to_string(),
+            ParsedWrapper::Alias(alias_wrapper) => alias_wrapper.name.to_string(),
+            ParsedWrapper::Constant(constant_wrapper) => constant_wrapper.name.to_string(),
         }
     }
 }
@@ -41,6 +64,30 @@ impl From<&Wrapper> for TokenStream2 {
                 parsed: ParsedWrapper::Struct(struct_wrapper),
                 ..
             } => struct_wrapper.into(),
+            Wrapper {
+                parsed: ParsedWrapper::GenericStruct(generic_wrapper),
+                ..
+            } => generic_wrapper.into(),
+            Wrapper {
+                parsed: ParsedWrapper::Enum(enum_wrapper),
+                ..
+            } => enum_wrapper.into(),
+            Wrapper {
+                parsed: ParsedWrapper::DataEnum(enum_wrapper),
+                ..
+            } => enum_wrapper.into(),
+            Wrapper {
+                parsed: ParsedWrapper::Impl(impl_wrapper),
+                ..
+            } => impl_wrapper.as_ref().into(),
+            Wrapper {
+                parsed: ParsedWrapper::Trait(trait_wrapper),
+                ..
+            } => trait_wrapper.into(),
+            Wrapper {
+                parsed: ParsedWrapper::Alias(alias_wrapper),
+                ..
+            } => alias_wrapper.into(),
             Wrapper {
                 parsed: ParsedWrapper::Function(function_wrapper),
                 original_definition,
@@ -51,6 +98,16 @@ impl From<&Wrapper> for TokenStream2 {
                     #tokens
                 }
             }
+            Wrapper {
+                parsed: ParsedWrapper::Constant(constant_wrapper),
+                original_definition,
+            } => {
+                let tokens: TokenStream2 = constant_wrapper.into();
+                quote! {
+                    #original_definition
+                    #tokens
+                }
+            }
         }
     }
 }
@@ -58,7 +115,14 @@ impl From<&Wrapper> for TokenStream2 {
 #[derive(Debug)]
 pub enum ParsedWrapper {
     Struct(StructWrapper),
+    GenericStruct(GenericStructWrapper),
     Function(FunctionWrapper),
+    Enum(EnumWrapper),
+    DataEnum(DataEnumWrapper),
+    Impl(Box<ImplWrapper>),
+    Trait(TraitWrapper),
+    Alias(AliasWrapper),
+    Constant(ConstantWrapper),
 }
 
 impl From<Wrapper> for TokenStream {
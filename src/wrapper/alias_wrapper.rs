@@ -0,0 +1,30 @@
+use std::fmt::Debug;
+
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use quote::quote;
+
+use super::TypeWrapper;
+
+/// `#[ffi]` type alias, Rust code is left as is as the aliased type is used in its place
+pub struct AliasWrapper {
+    pub(crate) name: Ident,
+    #[cfg_attr(not(any(feature = "cpp", feature = "swift")), allow(dead_code))]
+    pub(crate) wrapper_type: TypeWrapper,
+    pub(crate) original_item_type: syn::ItemType,
+}
+
+impl Debug for AliasWrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AliasWrapper")
+            .field("name", &self.name)
+            .field("wrapper_type", &self.wrapper_type)
+            .finish()
+    }
+}
+
+impl From<&AliasWrapper> for TokenStream2 {
+    fn from(alias_wrapper: &AliasWrapper) -> TokenStream2 {
+        let item_type = &alias_wrapper.original_item_type;
+        quote! {#item_type}
+    }
+}
@@ -1,20 +1,244 @@
 use crate::TokenStream2;
-use quote::quote;
+use quote::{format_ident, quote};
 
 pub const SLICE_GET_LEN_FN_NAME: &str = "__hiFfi__$slice_len";
 pub const SLICE_GET_PTR_FN_NAME: &str = "__hiFfi__$slice_ptr";
 pub const SLICE_DROP_FN_NAME: &str = "__hiFfi__$slice_drop";
 
+pub const BUFFER_DROP_FN_NAME: &str = "__hiFfi__$buffer_drop";
+
+pub const MAP_ITER_LEN_FN_NAME: &str = "__hiFfi__$map_iter_len";
+pub const MAP_ITER_NEXT_FN_NAME: &str = "__hiFfi__$map_iter_next";
+pub const MAP_ITER_DROP_FN_NAME: &str = "__hiFfi__$map_iter_drop";
+
+/// C types of the Rust primitives passed by value, the C and C++ headers alias them under the Rust
+/// names, which are used by every extern declaration. `bool` is `bool` in both languages.
+pub const C_PRIMITIVE_TYPES: [(&str, &str); 12] = [
+    ("u8", "uint8_t"),
+    ("u16", "uint16_t"),
+    ("u32", "uint32_t"),
+    ("u64", "uint64_t"),
+    ("usize", "size_t"),
+    ("i8", "int8_t"),
+    ("i16", "int16_t"),
+    ("i32", "int32_t"),
+    ("i64", "int64_t"),
+    ("isize", "intptr_t"),
+    ("f32", "float"),
+    ("f64", "double"),
+];
+
+/// Tuples are passed as `FfiTuple{N}` structs, which are generated up to this length
+pub const MAX_TUPLE_LEN: usize = 4;
+
 pub const RUST_STRING_DROP_FN_NAME: &str = "__hiFfi__$rust_string_drop";
 pub const RUST_STRING_DATA_FN_NAME: &str = "__hiFfi__$rust_string_data";
 pub const RUST_STRING_LEN_FN_NAME: &str = "__hiFfi__$rust_string_len";
 
+/// Name of the struct passing `i128` or `u128` as its 64-bit words
+pub fn int128_ffi_name(ty: impl std::fmt::Display) -> String {
+    format!("Ffi{}", ty.to_string().to_uppercase())
+}
+
 pub fn rust_code_base() -> TokenStream2 {
+    let tuples = (2..=MAX_TUPLE_LEN).map(|len| {
+        let name = format_ident!("FfiTuple{len}");
+        let params = (0..len).map(|index| format_ident!("T{index}"));
+        let fields = (0..len).map(|index| {
+            let field = format_ident!("_{index}");
+            let param = format_ident!("T{index}");
+            quote! {pub #field: #param}
+        });
+        quote! {
+            #[doc(hidden)]
+            #[repr(C)]
+            pub struct #name<#(#params),*> {
+                #(#fields,)*
+            }
+        }
+    });
+
+    // 128-bit integers are passed as 64-bit words, as their C ABI is not portable
+    let int128s = [("i128", "i64"), ("u128", "u64")].map(|(ty, hi_type)| {
+        let name = format_ident!("{}", int128_ffi_name(ty));
+        let (ty, hi_type) = (format_ident!("{ty}"), format_ident!("{hi_type}"));
+        quote! {
+            #[doc(hidden)]
+            #[repr(C)]
+            #[derive(Clone, Copy)]
+            pub struct #name {
+                pub lo: u64,
+                pub hi: #hi_type,
+            }
+
+            impl From<#ty> for #name {
+                fn from(value: #ty) -> Self {
+                    #name {
+                        lo: value as u64,
+                        hi: (value >> 64) as #hi_type,
+                    }
+                }
+            }
+
+            impl From<#name> for #ty {
+                fn from(value: #name) -> Self {
+                    (#ty::from(value.hi) << 64) | #ty::from(value.lo)
+                }
+            }
+        }
+    });
+
     quote! {
+        #(#tuples)*
+
+        #(#int128s)*
+
+        // fixed-size array passed by value, so it is copied without allocating
+        #[doc(hidden)]
+        #[repr(C)]
+        pub struct FfiArray<T, const N: usize> {
+            pub items: [T; N],
+        }
+
         #[repr(C)]
+        #[derive(Clone, Copy)]
         pub struct FfiSlice {
             pub ptr: *const u8,
             pub len: usize,
+            // frees the items when the slice owns them, `None` for borrowed data
+            pub drop: Option<unsafe extern "C" fn(*const u8, usize)>,
+        }
+
+        impl FfiSlice {
+            #[doc(hidden)]
+            pub fn from_vec<T>(items: Vec<T>) -> *mut FfiSlice {
+                unsafe extern "C" fn drop_items<T>(ptr: *const u8, len: usize) {
+                    unsafe {
+                        let _ = Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr as *mut T, len));
+                    }
+                }
+
+                let items = items.into_boxed_slice();
+                let len = items.len();
+                Box::into_raw(Box::new(FfiSlice {
+                    ptr: Box::into_raw(items) as *const u8,
+                    len,
+                    drop: Some(drop_items::<T>),
+                }))
+            }
+
+            #[doc(hidden)]
+            pub unsafe fn as_slice<'a, T>(&self) -> &'a [T] {
+                if self.len == 0 {
+                    &[]
+                } else {
+                    unsafe { std::slice::from_raw_parts(self.ptr as *const T, self.len) }
+                }
+            }
+        }
+
+        #[doc(hidden)]
+        #[repr(C)]
+        pub struct FfiBuffer {
+            // bytes owned by the target language, which frees them with `buffer_drop`
+            pub ptr: *mut u8,
+            pub len: usize,
+        }
+
+        impl FfiBuffer {
+            #[doc(hidden)]
+            pub fn new(bytes: impl Into<Box<[u8]>>) -> FfiBuffer {
+                let bytes: Box<[u8]> = bytes.into();
+                let len = bytes.len();
+                FfiBuffer {
+                    ptr: Box::into_raw(bytes) as *mut u8,
+                    len,
+                }
+            }
+        }
+
+        #[doc(hidden)]
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        pub struct FfiMap {
+            // keys and values borrowed from the caller, entries share the same index
+            pub keys: FfiSlice,
+            pub values: FfiSlice,
+        }
+
+        /// Entries of a map returned to the target language, converted one by one in `map_iter_next`
+        #[doc(hidden)]
+        pub struct FfiMapIter {
+            len: usize,
+            // writes the next converted key and value, returns `false` when there are no entries left
+            next: Box<dyn FnMut(*mut std::ffi::c_void, *mut std::ffi::c_void) -> bool>,
+        }
+
+        impl FfiMapIter {
+            #[doc(hidden)]
+            pub fn new<K, V>(
+                len: usize,
+                mut entries: impl Iterator<Item = (K, V)> + 'static,
+            ) -> *mut FfiMapIter {
+                let next = move |key: *mut std::ffi::c_void, value: *mut std::ffi::c_void| {
+                    match entries.next() {
+                        Some((next_key, next_value)) => {
+                            unsafe {
+                                (key as *mut K).write(next_key);
+                                (value as *mut V).write(next_value);
+                            }
+                            true
+                        }
+                        None => false,
+                    }
+                };
+                Box::into_raw(Box::new(FfiMapIter {
+                    len,
+                    next: Box::new(next),
+                }))
+            }
+        }
+
+        #[doc(hidden)]
+        #[repr(C)]
+        pub struct FfiCallback<F> {
+            pub call: F,
+            // closure of the target language, released with `drop` once Rust drops the callback
+            pub context: *mut std::ffi::c_void,
+            pub drop: unsafe extern "C" fn(*mut std::ffi::c_void),
+        }
+
+
+        impl<F> Drop for FfiCallback<F> {
+            fn drop(&mut self) {
+                unsafe { (self.drop)(self.context) }
+            }
+        }
+
+        /// Callback of a `+ Send` signature, the target language closure has to be safe to call
+        /// from other threads
+        #[doc(hidden)]
+        pub struct FfiSendCallback<F>(pub FfiCallback<F>);
+
+        unsafe impl<F> Send for FfiSendCallback<F> {}
+
+        #[doc(hidden)]
+        #[repr(C)]
+        pub struct FfiError {
+            // `String` with the error message, set only when the function fails
+            pub message: *mut String,
+            // value of `#[ffi]` data-carrying enum error, owned by the caller
+            pub variant: *mut std::ffi::c_void,
+            // value of `#[ffi]` C-like enum error
+            pub repr: i64,
+        }
+
+        #[doc(hidden)]
+        pub trait FfiEnum {
+            type Repr;
+
+            fn from_repr(value: Self::Repr) -> Self;
+            fn into_repr(&self) -> Self::Repr;
         }
 
         #[doc(hidden)]
@@ -36,6 +260,45 @@ pub fn rust_code_base() -> TokenStream2 {
         #[doc(hidden)]
         #[export_name = #SLICE_DROP_FN_NAME]
         pub unsafe extern "C" fn slice_drop(_self: *mut FfiSlice) {
+            unsafe {
+                let slice = Box::from_raw(_self);
+                if let Some(drop) = slice.drop {
+                    drop(slice.ptr, slice.len);
+                }
+            }
+        }
+
+        #[doc(hidden)]
+        #[export_name = #BUFFER_DROP_FN_NAME]
+        pub unsafe extern "C" fn buffer_drop(buffer: FfiBuffer) {
+            unsafe {
+                let _ = Box::from_raw(std::ptr::slice_from_raw_parts_mut(buffer.ptr, buffer.len));
+            }
+        }
+
+        #[doc(hidden)]
+        #[export_name = #MAP_ITER_LEN_FN_NAME]
+        pub unsafe extern "C" fn map_iter_len(_self: *const FfiMapIter) -> usize {
+            unsafe {
+                (*_self).len
+            }
+        }
+
+        #[doc(hidden)]
+        #[export_name = #MAP_ITER_NEXT_FN_NAME]
+        pub unsafe extern "C" fn map_iter_next(
+            _self: *mut FfiMapIter,
+            key: *mut std::ffi::c_void,
+            value: *mut std::ffi::c_void,
+        ) -> bool {
+            unsafe {
+                ((*_self).next)(key, value)
+            }
+        }
+
+        #[doc(hidden)]
+        #[export_name = #MAP_ITER_DROP_FN_NAME]
+        pub unsafe extern "C" fn map_iter_drop(_self: *mut FfiMapIter) {
             unsafe {
                 let _ = Box::from_raw(_self);
             }
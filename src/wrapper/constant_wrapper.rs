@@ -0,0 +1,55 @@
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+
+use super::TypeWrapper;
+
+/// `#[ffi]` const or static item. A literal value is copied to the target languages, otherwise the
+/// value is read with the getter extern function.
+pub struct ConstantWrapper {
+    pub(crate) name: Ident,
+    pub(crate) extern_function_name: String,
+    pub(crate) wrapper_type: TypeWrapper,
+    pub(crate) literal: Option<ConstantLiteral>,
+}
+
+/// Literal value of a constant
+#[derive(Debug)]
+#[cfg_attr(not(any(feature = "cpp", feature = "swift")), allow(dead_code))]
+pub enum ConstantLiteral {
+    Int(String), // decimal digits, with `-` when negative
+    Float(f64),
+    Bool(bool),
+    Char(char),
+    Str(String),
+}
+
+impl std::fmt::Debug for ConstantWrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConstantWrapper")
+            .field("name", &self.name)
+            .field("wrapper_type", &self.wrapper_type)
+            .field("literal", &self.literal)
+            .finish()
+    }
+}
+
+impl From<&ConstantWrapper> for TokenStream2 {
+    fn from(constant_wrapper: &ConstantWrapper) -> Self {
+        if constant_wrapper.literal.is_some() {
+            return quote! {};
+        }
+        let name = &constant_wrapper.name;
+        let extern_function_name = &constant_wrapper.extern_function_name;
+        let wrapper_name = format_ident!("ffi_constant_{}", name.to_string().to_lowercase());
+        let out_type = constant_wrapper.wrapper_type.rust_out_type();
+        let value = constant_wrapper.wrapper_type.rust_into_ffi(quote! {#name});
+
+        quote! {
+            #[doc(hidden)]
+            #[export_name = #extern_function_name]
+            pub extern "C" fn #wrapper_name() -> #out_type {
+                #value
+            }
+        }
+    }
+}
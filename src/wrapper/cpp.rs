@@ -1,28 +1,67 @@
 use super::*;
-use crate::wrapper::base::*;
 
+use alias_definition::*;
 use class_definition::*;
+use constant_definition::*;
+use data_enum_definition::*;
+use enum_definition::*;
 use function_definition::*;
+use trait_definition::*;
 
+pub mod alias_definition;
 pub mod class_definition;
+pub mod constant_definition;
+pub mod data_enum_definition;
+pub mod enum_definition;
 pub mod function_definition;
+pub mod trait_definition;
+pub mod type_mapping;
 
 pub struct CppFiles {
+    pub name: String,
     pub header: String,
     pub source: Option<String>,
 }
 
 impl Wrapper {
-    pub fn cpp(&self) -> CppFiles {
+    /// Files of the item, a generic struct has a header for every instantiation
+    pub fn cpp(&self) -> Vec<CppFiles> {
+        let files = |header, source| {
+            vec![CppFiles {
+                name: self.name(),
+                header,
+                source,
+            }]
+        };
         match &self.parsed {
-            ParsedWrapper::Struct(struct_wrapper) => CppFiles {
-                header: gen_class_definition(struct_wrapper),
-                source: None,
-            },
-            ParsedWrapper::Function(function_wrapper) => CppFiles {
-                header: gen_function_declaration(function_wrapper),
-                source: Some(gen_function_definition(function_wrapper)),
-            },
+            ParsedWrapper::Struct(struct_wrapper) => {
+                files(gen_class_definition(struct_wrapper), None)
+            }
+            ParsedWrapper::GenericStruct(generic_wrapper) => generic_wrapper
+                .instances
+                .iter()
+                .map(|StructInstance { struct_wrapper, .. }| CppFiles {
+                    name: struct_wrapper.name.to_string(),
+                    header: gen_class_definition(struct_wrapper),
+                    source: None,
+                })
+                .collect(),
+            ParsedWrapper::Function(function_wrapper) => files(
+                gen_function_declaration(function_wrapper),
+                Some(gen_function_definition(function_wrapper)),
+            ),
+            ParsedWrapper::Enum(enum_wrapper) => files(gen_enum_definition(enum_wrapper), None),
+            ParsedWrapper::DataEnum(enum_wrapper) => {
+                files(gen_data_enum_definition(enum_wrapper), None)
+            }
+            ParsedWrapper::Impl(impl_wrapper) => {
+                files(gen_class_definition(&impl_wrapper.struct_wrapper), None)
+            }
+            ParsedWrapper::Trait(trait_wrapper) => files(gen_trait_definition(trait_wrapper), None),
+            ParsedWrapper::Alias(alias_wrapper) => files(gen_alias_definition(alias_wrapper), None),
+            ParsedWrapper::Constant(constant_wrapper) => {
+                files(gen_constant_definition(constant_wrapper), None)
+            }
         }
     }
 }
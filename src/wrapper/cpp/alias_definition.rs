@@ -0,0 +1,20 @@
+use super::*;
+
+pub fn gen_alias_definition(alias_wrapper: &AliasWrapper) -> String {
+    let alias_name = &alias_wrapper.name;
+    let includes = alias_wrapper.wrapper_type.cpp_includes();
+    let cpp_type = alias_wrapper.wrapper_type.cpp_type();
+
+    format!(
+        r#"
+#ifndef {alias_name}__def
+#define {alias_name}__def
+
+#include "base.h"
+{includes}
+using {alias_name} = {cpp_type};
+
+#endif
+"#
+    )
+}
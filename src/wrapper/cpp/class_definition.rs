@@ -1,12 +1,10 @@
 use std::{collections::HashSet, fmt::Display};
 
-use quote::ToTokens;
-
 use super::*;
 
 pub fn gen_class_definition(struct_wrapper: &StructWrapper) -> String {
     let class_name = &struct_wrapper.name;
-    let (method_definitions, extern_fns, includes) =
+    let (mut method_definitions, mut extern_fns, mut includes) =
         struct_wrapper.fields.iter().map(map_fields).fold(
             (String::new(), String::new(), HashSet::new()),
             |(mut methods, mut externs, mut includes), Methods { getter, setter }| {
@@ -35,11 +33,33 @@ pub fn gen_class_definition(struct_wrapper: &StructWrapper) -> String {
                 (methods, externs, includes)
             },
         );
-    let default_constructor = default_constructor(struct_wrapper);
-    let default_constructor_definition = default_constructor.definition;
-    let default_constructor_extern_fn = default_constructor.extern_fn;
+    for Method {
+        definition,
+        extern_fn,
+        include,
+    } in struct_wrapper.methods.iter().map(map_method)
+    {
+        method_definitions.push_str(&definition);
+        extern_fns.push_str(&extern_fn);
+        includes.insert(include);
+    }
+
+    let (mut constructor_definitions, mut constructor_extern_fns) = (String::new(), String::new());
+    for Method {
+        definition,
+        extern_fn,
+        include,
+    } in struct_wrapper
+        .constructors
+        .iter()
+        .map(|constructor| map_constructor(constructor, struct_wrapper.is_initializer(constructor)))
+    {
+        constructor_definitions.push_str(&definition);
+        constructor_extern_fns.push_str(&extern_fn);
+        includes.insert(include);
+    }
 
-    let destructor = destructor(struct_wrapper);
+    let destructor = destructor(class_name, &struct_wrapper.drop_ext_fn_name);
     let destructor_definition = destructor.definition;
     let destructor_extern_fn = destructor.extern_fn;
 
@@ -49,9 +69,10 @@ pub fn gen_class_definition(struct_wrapper: &StructWrapper) -> String {
     });
 
     let pointer_constructor_definition = pointer_constructor_definition(class_name);
-    let copy_constructor = copy_constructor_definition(struct_wrapper);
-    let move_constructor = move_constructor_definition(struct_wrapper);
-    let clone_extern_fn = clone_ext_fn(struct_wrapper);
+    let copy_constructor =
+        copy_constructor_definition(class_name, &struct_wrapper.clone_ext_fn_name);
+    let move_constructor = move_constructor_definition(class_name);
+    let clone_extern_fn = clone_ext_fn(&struct_wrapper.clone_ext_fn_name);
 
     format!(
         r#"
@@ -63,8 +84,7 @@ pub fn gen_class_definition(struct_wrapper: &StructWrapper) -> String {
 
 extern "C" {{
 {extern_fns}
-{default_constructor_extern_fn}
-{destructor_extern_fn}
+{constructor_extern_fns}{destructor_extern_fn}
 {clone_extern_fn}
 }}
 
@@ -74,11 +94,11 @@ public:
 {pointer_constructor_definition}
 {copy_constructor}
 {move_constructor}
-{default_constructor_definition}
+{constructor_definitions}
 {destructor_definition}
 {method_definitions}
 
-    void* self_ptr() {{
+    void* self_ptr() const {{
         return self;
     }}
     void set_self_ptr(void* ptr) {{
@@ -91,14 +111,14 @@ public:
     )
 }
 
-fn clone_ext_fn(struct_wrapper: &StructWrapper) -> String {
-    let clone_ext_fn_name = &struct_wrapper.clone_ext_fn_name;
+pub(super) fn clone_ext_fn(clone_ext_fn_name: impl Display) -> String {
     format!("    void* {clone_ext_fn_name}(void*);\n",)
 }
 
-fn copy_constructor_definition(struct_wrapper: &StructWrapper) -> String {
-    let class_name = &struct_wrapper.name;
-    let clone_ext_fn_name = &struct_wrapper.clone_ext_fn_name;
+pub(super) fn copy_constructor_definition(
+    class_name: impl Display,
+    clone_ext_fn_name: impl Display,
+) -> String {
     format!(
         r#"
     {class_name}(const {class_name}& other) {{
@@ -107,8 +127,7 @@ fn copy_constructor_definition(struct_wrapper: &StructWrapper) -> String {
     )
 }
 
-fn move_constructor_definition(struct_wrapper: &StructWrapper) -> String {
-    let class_name = &struct_wrapper.name;
+pub(super) fn move_constructor_definition(class_name: impl Display) -> String {
     format!(
         r#"
     {class_name}({class_name}&& other) {{
@@ -118,220 +137,168 @@ fn move_constructor_definition(struct_wrapper: &StructWrapper) -> String {
     )
 }
 
-fn pointer_constructor_definition(class_name: impl Display) -> String {
+pub(super) fn pointer_constructor_definition(class_name: impl Display) -> String {
     format!(r#"    {class_name}(void* self) : self(self) {{}}"#,)
 }
 
-fn map_fields(field: &FieldWrapper) -> Methods {
-    match field {
-        FieldWrapper {
-            field_type,
-            wrapper_type: FieldWrapperType::Primitive,
-            setter,
-            getter,
-            ..
-        } => {
-            let field_type = quote::quote! { #field_type }.to_string();
-
-            let getter = getter
-                .as_ref()
-                .map(|g| map_primitive_getter(g, &field_type));
-            let setter = setter
-                .as_ref()
-                .map(|s| map_primitive_setter(s, &field_type));
-
-            Methods { getter, setter }
-        }
-
-        FieldWrapper {
-            wrapper_type: FieldWrapperType::String,
-            setter,
-            getter,
-            ..
-        } => {
-            let getter = getter.as_ref().map(map_string_getter);
-            let setter = setter.as_ref().map(map_string_setter);
-            Methods { getter, setter }
-        }
-
-        FieldWrapper {
-            wrapper_type: FieldWrapperType::Custom,
-            setter,
-            getter,
-            field_type,
-            ..
-        } => {
-            let getter = getter
-                .as_ref()
-                .map(|g| map_custom_getter(g, field_type.to_token_stream().to_string()));
-            let setter = setter
-                .as_ref()
-                .map(|s| map_custom_setter(s, field_type.to_token_stream().to_string()));
-            Methods { getter, setter }
-        }
-    }
+fn map_fields(
+    FieldWrapper {
+        wrapper_type,
+        getter,
+        setter,
+        ..
+    }: &FieldWrapper,
+) -> Methods {
+    let getter = getter.as_ref().map(|g| map_getter(g, wrapper_type));
+    let setter = setter.as_ref().map(|s| map_setter(s, wrapper_type));
+    Methods { getter, setter }
 }
 
-fn map_custom_getter(
+fn map_getter(
     Getter {
         name,
         extern_fn_name,
     }: &Getter,
-    field_type: impl Display,
+    wrapper_type: &TypeWrapper,
 ) -> Method {
+    let field_type = wrapper_type.cpp_type();
+    let out_type = wrapper_type.c_out_type();
+    let result = wrapper_type.cpp_from_ffi(format!("{extern_fn_name}(this->self)"));
     Method {
         definition: format!(
             r#"
     {field_type} {name}() {{
-        return {field_type}({extern_fn_name}(this->self));
+        return {result};
     }}"#
         ),
-        extern_fn: format!("    void* {extern_fn_name}(void*);\n"),
-        include: custom_type_include(field_type),
+        extern_fn: format!("    {out_type} {extern_fn_name}(void*);\n"),
+        include: wrapper_type.cpp_includes(),
     }
 }
 
-fn map_custom_setter(
+fn map_setter(
     Setter {
         name,
         extern_fn_name,
     }: &Setter,
-    field_type: impl Display,
+    wrapper_type: &TypeWrapper,
 ) -> Method {
+    let arg_type = wrapper_type.cpp_arg_type();
+    let in_type = wrapper_type.c_in_type();
+    let value = wrapper_type.cpp_to_ffi("value");
     Method {
         definition: format!(
             r#"
-    void {name}({field_type}& value) {{
-        {extern_fn_name}(this->self, value.self_ptr()); // Rust side makes clone
+    void {name}({arg_type} value) {{
+        {extern_fn_name}(this->self, {value});
     }}"#
         ),
-        extern_fn: format!("    void {extern_fn_name}(void*, void*);\n"),
-        include: custom_type_include(field_type),
+        extern_fn: format!("    void {extern_fn_name}(void*, {in_type});\n"),
+        include: wrapper_type.cpp_includes(),
     }
 }
 
-fn custom_type_include(field_type: impl Display) -> String {
-    format!("#include \"{field_type}.h\"\n")
-}
-
-fn map_string_getter(
-    Getter {
-        name,
-        extern_fn_name,
-    }: &Getter,
-) -> Method {
+fn map_method(function_wrapper: &FunctionWrapper) -> Method {
+    let name = &function_wrapper.name;
+    let constness = match function_wrapper.receiver {
+        Some(ReceiverWrapper { mutable: false, .. }) => " const",
+        _ => "",
+    };
+    let FunctionParts {
+        args_signatures,
+        extern_fn,
+        return_type,
+        body,
+        includes,
+        ..
+    } = function_parts(function_wrapper, "        ");
     Method {
         definition: format!(
             r#"
-    std::string {name}() {{
-        void* slice_ptr = {extern_fn_name}(this->self);
-        char* ptr = {SLICE_GET_PTR_FN_NAME}(slice_ptr);
-        auto len = {SLICE_GET_LEN_FN_NAME}(slice_ptr);
-        auto result = std::string(ptr, len);
-        {SLICE_DROP_FN_NAME}(slice_ptr);
-        return result;
+    {return_type} {name}({args_signatures}){constness} {{
+        {body}
     }}"#
         ),
-        extern_fn: format!("    void* {extern_fn_name}(void*);\n"),
-        include: String::new(),
+        extern_fn,
+        include: class_includes(function_wrapper, includes),
     }
 }
 
-fn map_string_setter(
-    Setter {
-        name,
-        extern_fn_name,
-    }: &Setter,
-) -> Method {
-    Method {
-        definition: format!(
+/// Initializers, `new` and `default`, become C++ constructors, other constructors are static
+/// factories. Fallible `cpp_expected` constructors are static factories, as C++ constructors
+/// cannot return an error.
+fn map_constructor(function_wrapper: &FunctionWrapper, is_initializer: bool) -> Method {
+    let name = &function_wrapper.name;
+    let FunctionParts {
+        args_signatures,
+        extern_fn,
+        return_type,
+        call,
+        body,
+        includes,
+    } = function_parts(function_wrapper, "        ");
+    let is_fallible = function_wrapper.error_wrapper.is_some();
+    let is_expected = function_wrapper
+        .error_wrapper
+        .as_ref()
+        .is_some_and(|error_wrapper| error_wrapper.cpp_expected);
+    let class_name = function_wrapper
+        .self_type
+        .as_ref()
+        .expect("No type of constructor");
+    let definition = if !is_initializer || is_expected {
+        // `new` is a C++ keyword
+        let name = match name.to_string().as_str() {
+            "new" if is_fallible => "try_new".to_string(),
+            "new" => "create".to_string(),
+            name => name.to_string(),
+        };
+        format!(
             r#"
-    void {name}(std::string&& value) {{
-        auto ptr = value.data();
-        auto len = value.size();
-        {extern_fn_name}(this->self, ptr, len);
-    }}
-    
-    void {name}(std::string& value) {{
-        this->{name}(std::move(value));
+    static {return_type} {name}({args_signatures}) {{
+        {body}
     }}"#
-        ),
-        extern_fn: format!("    void {extern_fn_name}(void*, char*, size_t);\n"),
-        include: String::new(),
-    }
-}
-
-fn map_primitive_getter(
-    Getter {
-        name,
-        extern_fn_name,
-    }: &Getter,
-    field_type: impl Display,
-) -> Method {
-    Method {
-        definition: format!(
+        )
+    } else if let Some(error_wrapper) = &function_wrapper.error_wrapper {
+        let error_check = gen_error_check(error_wrapper, "        ");
+        format!(
             r#"
-    {field_type} {name}() {{
-        return {field_type}({extern_fn_name}(this->self));
+    {class_name}({args_signatures}) {{
+        FfiError ffi_error{{nullptr, nullptr, 0}};
+        this->self = {call};
+        {error_check}
     }}"#
-        ),
-        extern_fn: format!("    {field_type} {extern_fn_name}(void*);\n"),
-        include: String::new(),
-    }
-}
-
-fn map_primitive_setter(
-    Setter {
-        name,
-        extern_fn_name,
-    }: &Setter,
-    field_type: impl Display,
-) -> Method {
-    Method {
-        definition: format!(
+        )
+    } else {
+        format!(
             r#"
-    void {name}({field_type} value) {{
-        {extern_fn_name}(this->self, value);
+    {class_name}({args_signatures}) {{
+        this->self = {call};
     }}"#
-        ),
-        extern_fn: format!("    void {extern_fn_name}(void*, {field_type});\n"),
-        include: String::new(),
+        )
+    };
+
+    Method {
+        definition,
+        extern_fn,
+        include: class_includes(function_wrapper, includes),
     }
 }
 
-fn default_constructor(struct_wrapper: &StructWrapper) -> Method {
-    let class_name = &struct_wrapper.name;
-
-    if let Some(default_constructor) = struct_wrapper.default_constructor.as_ref() {
-        let default_constructor_ext_fn_name = &default_constructor.extern_fn_name;
-
-        let definition = format!(
-            r#"
-    {class_name}() {{
-        this->self = {default_constructor_ext_fn_name}();
-    }}"#,
-        );
-
-        let extern_fn = format!("    void* {default_constructor_ext_fn_name}();");
-
-        Method {
-            definition,
-            extern_fn,
-            include: String::new(),
-        }
-    } else {
-        Method {
-            definition: String::new(),
-            extern_fn: String::new(),
-            include: String::new(),
-        }
-    }
+/// Includes of a class member, the class is complete within its own member bodies
+fn class_includes(function_wrapper: &FunctionWrapper, includes: Vec<String>) -> String {
+    let own_include = function_wrapper.self_type.as_ref().map(custom_type_include);
+    includes
+        .into_iter()
+        .filter(|include| Some(include) != own_include.as_ref())
+        .collect()
 }
 
-fn destructor(struct_wrapper: &StructWrapper) -> Method {
-    let class_name = &struct_wrapper.name;
-    let drop_ext_fn_name = &struct_wrapper.drop_ext_fn_name;
+pub(super) fn custom_type_include(field_type: impl Display) -> String {
+    format!("#include \"{field_type}.h\"\n")
+}
 
+pub(super) fn destructor(class_name: impl Display, drop_ext_fn_name: impl Display) -> Method {
     let definition = format!(
         r#"
     virtual ~{class_name}() {{
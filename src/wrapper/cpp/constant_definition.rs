@@ -0,0 +1,73 @@
+use super::*;
+
+/// Literal values are `constexpr`, other values are read once from Rust
+pub fn gen_constant_definition(constant_wrapper: &ConstantWrapper) -> String {
+    let name = &constant_wrapper.name;
+    let wrapper_type = &constant_wrapper.wrapper_type;
+    let cpp_type = wrapper_type.cpp_type();
+
+    let definition = match &constant_wrapper.literal {
+        Some(literal) => format!("constexpr {cpp_type} {name}{{{}}};", cpp_literal(literal)),
+        None => {
+            let extern_fn_name = &constant_wrapper.extern_function_name;
+            let value = wrapper_type.cpp_from_ffi(format!("{extern_fn_name}()"));
+            format!(
+                r#"extern "C" {{
+    {} {extern_fn_name}();
+}}
+
+inline const {cpp_type} {name} = {value};"#,
+                wrapper_type.c_out_type()
+            )
+        }
+    };
+
+    format!(
+        r#"
+#ifndef {name}__def
+#define {name}__def
+
+#include "base.h"
+
+{definition}
+
+#endif
+"#
+    )
+}
+
+/// Strings are passed with their length, as they may contain null characters
+fn cpp_literal(literal: &ConstantLiteral) -> String {
+    match literal {
+        // unsuffixed literals above `i64::MAX` have no type, and `-9223372036854775808` negates an
+        // out of range literal, so the literals are 64-bit and `i64::MIN` is written as expression
+        ConstantLiteral::Int(digits) => match digits.strip_prefix('-') {
+            Some("9223372036854775808") => "(-9223372036854775807LL - 1)".to_string(),
+            Some(_) => format!("{digits}LL"),
+            None => format!("{digits}ULL"),
+        },
+        ConstantLiteral::Float(value) => format!("{value:?}"),
+        ConstantLiteral::Bool(value) => value.to_string(),
+        ConstantLiteral::Char(value) => match value {
+            '\'' | '\\' => format!("U'\\{value}'"),
+            value if value.is_control() => format!("static_cast<char32_t>({})", *value as u32),
+            value => format!("U'{value}'"),
+        },
+        ConstantLiteral::Str(value) => {
+            let escaped = value
+                .chars()
+                .map(|c| match c {
+                    '"' | '\\' => format!("\\{c}"),
+                    // octal escapes, unlike hex ones, don't take the following digits
+                    c if c.is_control() => c
+                        .to_string()
+                        .bytes()
+                        .map(|byte| format!("\\{byte:03o}"))
+                        .collect(),
+                    c => c.to_string(),
+                })
+                .collect::<String>();
+            format!("\"{escaped}\", {}", value.len())
+        }
+    }
+}
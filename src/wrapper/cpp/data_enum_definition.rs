@@ -0,0 +1,182 @@
+use std::collections::BTreeSet;
+
+use super::class_definition::{
+    clone_ext_fn, copy_constructor_definition, destructor, move_constructor_definition,
+    pointer_constructor_definition,
+};
+use super::*;
+
+/// Variant structs are named `{Variant}Variant`, so a variant can be named after its payload type
+pub fn gen_data_enum_definition(enum_wrapper: &DataEnumWrapper) -> String {
+    let class_name = &enum_wrapper.name;
+    let tag_ext_fn_name = &enum_wrapper.tag_ext_fn_name;
+
+    let mut includes = BTreeSet::new();
+    let mut extern_fns = String::new();
+    let mut variant_structs = String::new();
+    let mut constructors = String::new();
+    let mut variant_cases = String::new();
+
+    for variant in &enum_wrapper.variants {
+        let variant_name = &variant.name;
+        let struct_name = variant_struct_name(variant_name);
+        let constructor_ext_fn_name = &variant.constructor_ext_fn_name;
+
+        let (mut members, mut getters, mut ext_args, mut constructor_args) =
+            (String::new(), Vec::new(), Vec::new(), Vec::new());
+        for field in &variant.fields {
+            let VariantField {
+                cpp_type,
+                getter_extern_type,
+                getter,
+                constructor_extern_type,
+                constructor_arg,
+                include,
+            } = map_variant_field(field);
+
+            if let Some(Getter { extern_fn_name, .. }) = &field.getter {
+                extern_fns.push_str(&format!(
+                    "    {getter_extern_type} {extern_fn_name}(void*);\n"
+                ));
+            }
+            members.push_str(&format!("        {cpp_type} {};\n", field.field_name));
+            getters.push(getter);
+            ext_args.push(constructor_extern_type);
+            constructor_args.push(constructor_arg);
+            includes.insert(include);
+        }
+        let ext_args = ext_args.join(", ");
+        extern_fns.push_str(&format!(
+            "    void* {constructor_ext_fn_name}({ext_args});\n"
+        ));
+
+        variant_structs.push_str(&format!(
+            r#"
+    struct {struct_name} {{
+{members}    }};
+"#
+        ));
+
+        let constructor_args = constructor_args.join(", ");
+        constructors.push_str(&format!(
+            r#"
+    {class_name}({struct_name} value) {{
+        this->self = {constructor_ext_fn_name}({constructor_args});
+    }}"#
+        ));
+
+        let getters = getters.join(", ");
+        variant_cases.push_str(&format!(
+            r#"
+            case Tag::{variant_name}:
+                return {struct_name}{{{getters}}};"#
+        ));
+    }
+
+    let tags = enum_wrapper
+        .variants
+        .iter()
+        .map(|variant| format!("        {},\n", variant.name))
+        .collect::<String>();
+    let variant_names = enum_wrapper
+        .variants
+        .iter()
+        .map(|variant| variant_struct_name(&variant.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let includes = includes.into_iter().collect::<String>();
+    let pointer_constructor_definition = pointer_constructor_definition(class_name);
+    let copy_constructor = copy_constructor_definition(class_name, &enum_wrapper.clone_ext_fn_name);
+    let move_constructor = move_constructor_definition(class_name);
+    let clone_extern_fn = clone_ext_fn(&enum_wrapper.clone_ext_fn_name);
+    let Method {
+        definition: destructor_definition,
+        extern_fn: destructor_extern_fn,
+        ..
+    } = destructor(class_name, &enum_wrapper.drop_ext_fn_name);
+
+    format!(
+        r#"
+#ifndef {class_name}__def
+#define {class_name}__def
+
+#include "base.h"
+#include <cstdlib>
+#include <variant>
+{includes}
+
+extern "C" {{
+    u32 {tag_ext_fn_name}(void*);
+{extern_fns}
+{destructor_extern_fn}
+{clone_extern_fn}
+}}
+
+class {class_name} {{
+    void* self = nullptr;
+public:
+    enum class Tag : u32 {{
+{tags}    }};
+{variant_structs}
+    using Variant = std::variant<{variant_names}>;
+
+{pointer_constructor_definition}
+{copy_constructor}
+{move_constructor}
+{constructors}
+{destructor_definition}
+
+    Tag tag() {{
+        return Tag({tag_ext_fn_name}(this->self));
+    }}
+
+    Variant variant() {{
+        switch (this->tag()) {{{variant_cases}
+        }}
+        std::abort();
+    }}
+
+    void* self_ptr() const {{
+        return self;
+    }}
+    void set_self_ptr(void* ptr) {{
+        self = ptr;
+    }}
+}};
+
+#endif
+"#
+    )
+}
+
+fn variant_struct_name(variant_name: impl std::fmt::Display) -> String {
+    format!("{variant_name}Variant")
+}
+
+struct VariantField {
+    cpp_type: String,
+    getter_extern_type: String,
+    getter: String,
+    constructor_extern_type: String,
+    constructor_arg: String,
+    include: String,
+}
+
+fn map_variant_field(field: &FieldWrapper) -> VariantField {
+    let field_name = &field.field_name;
+    let wrapper_type = &field.wrapper_type;
+    let getter_call = match &field.getter {
+        Some(Getter { extern_fn_name, .. }) => format!("{extern_fn_name}(this->self)"),
+        None => unreachable!("Variant fields always have getters"),
+    };
+
+    VariantField {
+        cpp_type: wrapper_type.cpp_type(),
+        getter_extern_type: wrapper_type.c_out_type(),
+        getter: wrapper_type.cpp_from_ffi(getter_call),
+        constructor_extern_type: wrapper_type.c_in_type(),
+        constructor_arg: wrapper_type.cpp_to_ffi(format!("value.{field_name}")),
+        include: wrapper_type.cpp_includes(),
+    }
+}
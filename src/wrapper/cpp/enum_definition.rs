@@ -0,0 +1,41 @@
+use super::*;
+
+pub fn gen_enum_definition(enum_wrapper: &EnumWrapper) -> String {
+    let enum_name = &enum_wrapper.name;
+    let repr = &enum_wrapper.repr;
+    let variants = enum_wrapper
+        .variants
+        .iter()
+        .map(|EnumVariant { name, discriminant }| match discriminant {
+            Some(discriminant) => format!("    {name} = {discriminant},\n"),
+            None => format!("    {name},\n"),
+        })
+        .collect::<String>();
+    let cases = enum_wrapper
+        .variants
+        .iter()
+        .map(|EnumVariant { name, .. }| format!("    case {enum_name}::{name}:\n"))
+        .collect::<String>();
+
+    format!(
+        r#"
+#ifndef {enum_name}__def
+#define {enum_name}__def
+
+#include "base.h"
+
+enum class {enum_name} : {repr} {{
+{variants}}};
+
+// Values not matching any variant (e.g. from `static_cast`) are rejected before they are passed to Rust
+inline {repr} {enum_name}_to_ffi({enum_name} value) {{
+    switch (value) {{
+{cases}        return static_cast<{repr}>(value);
+    }}
+    reject_invalid_value("Invalid {enum_name} value");
+}}
+
+#endif
+"#
+    )
+}
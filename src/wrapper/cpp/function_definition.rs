@@ -1,138 +1,210 @@
 use crate::wrapper::cpp::*;
-use quote::ToTokens;
 
 pub fn gen_function_declaration(function_wrapper: &FunctionWrapper) -> String {
     let fn_name = function_wrapper.name.to_string();
-    let extern_fn_name = &function_wrapper.extern_function_name;
-    let (mut cpp_args, mut wrapper_args) = (Vec::new(), Vec::new());
-    function_wrapper
-        .args_wrappers
-        .iter()
-        .for_each(|arg| match arg {
-            FunctionArgWrapper {
-                arg_name,
-                arg_type,
-                wrapper_type: FunctionArgWrapperType::Primitive,
-            } => {
-                cpp_args.push(format!("{} {}", arg_type.to_token_stream(), arg_name));
-                wrapper_args.push(format!("{} {}", arg_type.to_token_stream(), arg_name));
-            }
-            FunctionArgWrapper {
-                arg_name,
-                wrapper_type: FunctionArgWrapperType::String,
-                ..
-            } => {
-                cpp_args.push(format!("std::string&& {arg_name}"));
-                wrapper_args.push(format!("char* {arg_name}"));
-            }
-        });
-
-    let cpp_args = cpp_args.join(", ");
-    let wrapper_args = wrapper_args.join(", ");
-
-    let ReturnTypes {
-        ext_return_type,
+    let FunctionParts {
+        args_signatures,
+        extern_fn,
         return_type,
+        includes,
         ..
-    } = map_return_type(&function_wrapper.return_wrapper);
+    } = function_parts(function_wrapper, "    ");
+    let includes = includes.concat();
 
     format!(
         r#"
 #include "base.h"
-
+{includes}
 extern "C" {{
-    {ext_return_type} {extern_fn_name}({wrapper_args});
-}}
+{extern_fn}}}
 
-{return_type} {fn_name}({cpp_args});
+{return_type} {fn_name}({args_signatures});
 "#
     )
 }
 
 pub fn gen_function_definition(function_wrapper: &FunctionWrapper) -> String {
     let fn_name = function_wrapper.name.to_string();
-    let extern_fn_name = &function_wrapper.extern_function_name;
-    let (mut args_signatures, mut args_names, mut args_casts): (Vec<_>, Vec<_>, Vec<_>) =
-        (Vec::new(), Vec::new(), Vec::new());
-    function_wrapper
-        .args_wrappers
-        .iter()
-        .for_each(|arg| match arg {
-            FunctionArgWrapper {
-                arg_name,
-                arg_type,
-                wrapper_type: FunctionArgWrapperType::Primitive,
-            } => {
-                args_signatures.push(format!("{} {}", arg_type.to_token_stream(), arg_name));
-                args_names.push(arg_name.to_string());
-            }
-
-            FunctionArgWrapper {
-                arg_name,
-                wrapper_type: FunctionArgWrapperType::String,
-                ..
-            } => {
-                args_signatures.push(format!("std::string&& {arg_name}"));
-                args_names.push(format!("casted_{arg_name}"));
-                args_casts.push(format!(
-                    r#"    auto casted_{arg_name} = {arg_name}.data();"#
-                ));
-            }
-        });
-    let args_signatures = args_signatures.join(", ");
-    let args_names = args_names.join(", ");
-    let args_casts = args_casts.join("\n");
-
-    let ReturnTypes {
+    let FunctionParts {
+        args_signatures,
         return_type,
-        return_cast,
-        ext_return_type,
-    } = map_return_type(&function_wrapper.return_wrapper);
+        body,
+        ..
+    } = function_parts(function_wrapper, "    ");
 
     format!(
         r#"
 #include "{fn_name}.h"
 
 {return_type} {fn_name}({args_signatures}) {{
-{args_casts}
-    {ext_return_type} result = {extern_fn_name}({args_names});
-{return_cast}
+    {body}
 }}
 "#
     )
 }
 
+/// Parts of the C++ wrapper shared by functions and methods, the instance of a method is passed
+/// to the extern function as `this->self`
+pub(super) struct FunctionParts {
+    pub(super) args_signatures: String,
+    pub(super) extern_fn: String,
+    pub(super) return_type: String,
+    pub(super) call: String,
+    pub(super) body: String,
+    pub(super) includes: Vec<String>,
+}
+
+pub(super) fn function_parts(function_wrapper: &FunctionWrapper, indent: &str) -> FunctionParts {
+    let extern_fn_name = &function_wrapper.extern_function_name;
+    let (mut args_signatures, mut extern_args, mut args_values, mut includes) =
+        (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    if function_wrapper.receiver.is_some() {
+        extern_args.push("void* self".to_string());
+        args_values.push("this->self".to_string());
+    }
+    function_wrapper.args_wrappers.iter().for_each(
+        |FunctionArgWrapper {
+             arg_name,
+             wrapper_type,
+         }| {
+            args_signatures.push(format!("{} {arg_name}", wrapper_type.cpp_arg_type()));
+            extern_args.push(format!("{} {arg_name}", wrapper_type.c_in_type()));
+            args_values.push(wrapper_type.cpp_to_ffi(arg_name));
+            includes.push(wrapper_type.cpp_includes());
+        },
+    );
+
+    if let Some(ErrorWrapper { wrapper_type, .. }) = &function_wrapper.error_wrapper {
+        extern_args.push("FfiError* error".to_string());
+        args_values.push("&ffi_error".to_string());
+        includes.extend(wrapper_type.as_ref().map(TypeWrapper::cpp_includes));
+    }
+
+    let args_signatures = args_signatures.join(", ");
+    let extern_args = extern_args.join(", ");
+    let args_values = args_values.join(", ");
+
+    let ReturnTypes {
+        ext_return_type,
+        return_type,
+        include,
+    } = map_return_type(
+        &function_wrapper.return_wrapper,
+        &function_wrapper.error_wrapper,
+    );
+    includes.push(include);
+    includes.sort();
+    includes.dedup();
+
+    let call = format!("{extern_fn_name}({args_values})");
+    let body = gen_call_body(
+        call.clone(),
+        &function_wrapper.return_wrapper,
+        &function_wrapper.error_wrapper,
+        indent,
+    );
+
+    FunctionParts {
+        args_signatures,
+        extern_fn: format!("    {ext_return_type} {extern_fn_name}({extern_args});\n"),
+        return_type,
+        call,
+        body,
+        includes,
+    }
+}
+
+/// Body of a function calling `call` and converting its result, errors are thrown as
+/// `hi_ffi::RustError` or returned in `hi_ffi::Expected` when the item is `cpp_expected`. Lines
+/// after the first one are indented with `indent`.
+pub(super) fn gen_call_body(
+    call: String,
+    return_wrapper: &Option<FunctionReturnWrapper>,
+    error_wrapper: &Option<ErrorWrapper>,
+    indent: &str,
+) -> String {
+    let Some(error_wrapper) = error_wrapper else {
+        return match return_wrapper {
+            Some(FunctionReturnWrapper { wrapper_type }) => {
+                format!("return {};", wrapper_type.cpp_from_ffi(call))
+            }
+            None => format!("{call};"),
+        };
+    };
+
+    let (call, result) = match return_wrapper {
+        Some(FunctionReturnWrapper { wrapper_type }) => (
+            format!("auto ffi_result = {call};"),
+            format!(
+                "\n{indent}return {};",
+                wrapper_type.cpp_from_ffi("ffi_result")
+            ),
+        ),
+        None if error_wrapper.cpp_expected => {
+            (format!("{call};"), format!("\n{indent}return {{}};"))
+        }
+        None => (format!("{call};"), String::new()),
+    };
+    let error_check = gen_error_check(error_wrapper, indent);
+    format!(
+        r#"FfiError ffi_error{{nullptr, nullptr, 0}};
+{indent}{call}
+{indent}{error_check}{result}"#
+    )
+}
+
+/// Throws the error stored in `ffi_error` or returns it when the item is `cpp_expected`
+pub(super) fn gen_error_check(error_wrapper: &ErrorWrapper, indent: &str) -> String {
+    let free_variant = match error_wrapper {
+        ErrorWrapper {
+            wrapper_type: Some(wrapper_type @ TypeWrapper::Custom(_)),
+            ..
+        } => format!(
+            "\n{indent}    {} ffi_variant(ffi_error.variant); // the error value is exposed in Swift only",
+            wrapper_type.cpp_type()
+        ),
+        _ => String::new(),
+    };
+    let on_error = if error_wrapper.cpp_expected {
+        "return"
+    } else {
+        "throw"
+    };
+    format!(
+        r#"if (ffi_error.message != nullptr) {{{free_variant}
+{indent}    {on_error} hi_ffi::RustError(RustString(ffi_error.message).to_string());
+{indent}}}"#
+    )
+}
+
 struct ReturnTypes {
     ext_return_type: String,
     return_type: String,
-    return_cast: String,
+    include: String,
 }
 
-fn map_return_type(return_wrapper: &Option<FunctionReturnWrapper>) -> ReturnTypes {
-    match return_wrapper {
-        Some(FunctionReturnWrapper {
-            wrapper_type: FunctionReturnWrapperType::Primitive,
-            return_type,
-        }) => ReturnTypes {
-            ext_return_type: return_type.to_token_stream().to_string(),
-            return_type: return_type.to_token_stream().to_string(),
-            return_cast: "    return result;".to_string(),
-        },
-        Some(FunctionReturnWrapper {
-            wrapper_type: FunctionReturnWrapperType::String,
-            ..
-        }) => ReturnTypes {
-            ext_return_type: "void*".to_string(),
-            return_type: "std::string".to_string(),
-            return_cast: "
-    auto rust_str = RustString(result);
-    return rust_str.to_string();"
-                .to_string(),
+fn map_return_type(
+    return_wrapper: &Option<FunctionReturnWrapper>,
+    error_wrapper: &Option<ErrorWrapper>,
+) -> ReturnTypes {
+    let return_types = match return_wrapper {
+        Some(FunctionReturnWrapper { wrapper_type }) => ReturnTypes {
+            ext_return_type: wrapper_type.c_out_type(),
+            return_type: wrapper_type.cpp_type(),
+            include: wrapper_type.cpp_includes(),
         },
         None => ReturnTypes {
-            ext_return_type: "void*".to_string(),
+            ext_return_type: "void".to_string(),
             return_type: "void".to_string(),
-            return_cast: "".to_string(),
+            include: String::new(),
+        },
+    };
+
+    match error_wrapper {
+        Some(error_wrapper) if error_wrapper.cpp_expected => ReturnTypes {
+            return_type: format!("hi_ffi::Expected<{}>", return_types.return_type),
+            ..return_types
         },
+        _ => return_types,
     }
 }
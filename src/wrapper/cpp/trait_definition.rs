@@ -0,0 +1,119 @@
+use std::fmt::Display;
+
+use super::*;
+
+/// Abstract class implemented in C++. Its vtable functions call the virtual methods of the
+/// implementation passed to Rust with `into_ffi`.
+pub fn gen_trait_definition(trait_wrapper: &TraitWrapper) -> String {
+    let trait_name = &trait_wrapper.name;
+    let ffi_name = trait_wrapper.ffi_name();
+    let vtable_name = trait_wrapper.vtable_name();
+    let c_declarations = trait_wrapper.c_declarations();
+
+    let mut includes = trait_wrapper
+        .methods
+        .iter()
+        .flat_map(|method| {
+            method
+                .args_wrappers
+                .iter()
+                .map(|arg| &arg.wrapper_type)
+                .chain(method.return_wrapper.as_ref().map(|r| &r.wrapper_type))
+        })
+        .map(TypeWrapper::cpp_includes)
+        .collect::<Vec<_>>();
+    includes.sort();
+    includes.dedup();
+    let includes = includes.concat();
+
+    let virtual_methods = trait_wrapper
+        .methods
+        .iter()
+        .map(virtual_method)
+        .collect::<String>();
+    let vtable_functions = trait_wrapper
+        .methods
+        .iter()
+        .map(|method| vtable_function(trait_name, method))
+        .collect::<String>();
+
+    format!(
+        r#"
+#ifndef {trait_name}__def
+#define {trait_name}__def
+
+#include "base.h"
+{includes}{c_declarations}
+class {trait_name} {{
+public:
+    virtual ~{trait_name}() = default;
+{virtual_methods}
+    // Passes the implementation to Rust, which deletes it once the trait object is dropped
+    static {ffi_name} into_ffi(std::unique_ptr<{trait_name}> implementation) {{
+        return {ffi_name}{{implementation.release(), {vtable_name}{{{vtable_functions}
+            [](void* self) noexcept {{ delete static_cast<{trait_name}*>(self); }},
+        }}}};
+    }}
+}};
+
+#endif
+"#
+    )
+}
+
+/// `&self` methods are `const`
+fn virtual_method(method: &TraitMethodWrapper) -> String {
+    let name = &method.name;
+    let args = method
+        .args_wrappers
+        .iter()
+        .map(|arg| format!("{} {}", arg.wrapper_type.cpp_type(), arg.arg_name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let return_type = method
+        .return_wrapper
+        .as_ref()
+        .map_or("void".to_string(), |r| r.wrapper_type.cpp_type());
+    let constness = if method.mutable { "" } else { " const" };
+    format!("    virtual {return_type} {name}({args}){constness} = 0;\n")
+}
+
+/// Lambda converting the arguments passed from Rust and calling the method, the result is kept
+/// in a variable while `set_result` copies it, as its FFI representation may borrow it. It is
+/// called from Rust, so exceptions can't unwind through it and terminate the program.
+fn vtable_function(trait_name: impl Display, method: &TraitMethodWrapper) -> String {
+    let name = &method.name;
+    let (args_signatures, args_values): (Vec<_>, Vec<_>) = method
+        .args_wrappers
+        .iter()
+        .enumerate()
+        .map(|(index, arg)| {
+            (
+                format!(", {} arg{index}", arg.wrapper_type.c_out_type()),
+                arg.wrapper_type.cpp_from_ffi(format!("arg{index}")),
+            )
+        })
+        .unzip();
+    let constness = if method.mutable { "" } else { "const " };
+    let call = format!(
+        "static_cast<{constness}{trait_name}*>(self)->{name}({})",
+        args_values.join(", ")
+    );
+    let args_signatures = args_signatures.concat();
+
+    match &method.return_wrapper {
+        Some(FunctionReturnWrapper { wrapper_type }) => format!(
+            r#"
+            [](void* self{args_signatures}, void* result, void (*set_result)(void*, {in_type})) noexcept {{
+                auto value = {call};
+                set_result(result, {value_to_ffi});
+            }},"#,
+            in_type = wrapper_type.c_in_type(),
+            value_to_ffi = wrapper_type.cpp_to_ffi("value"),
+        ),
+        None => format!(
+            r#"
+            [](void* self{args_signatures}) noexcept {{ {call}; }},"#
+        ),
+    }
+}
@@ -0,0 +1,250 @@
+use std::fmt::Display;
+
+use super::*;
+
+impl TypeWrapper {
+    /// Type exposed in the C++ API
+    pub(super) fn cpp_type(&self) -> String {
+        match self {
+            TypeWrapper::Primitive(ty) | TypeWrapper::Int128(ty) => ty.to_string(),
+            TypeWrapper::Custom(ty) => ty.to_string(),
+            TypeWrapper::Char => "char32_t".to_string(),
+            TypeWrapper::String | TypeWrapper::Str => "std::string".to_string(),
+            TypeWrapper::StaticStr => "std::string_view".to_string(),
+            TypeWrapper::Enum { name, .. } => name.to_string(),
+            TypeWrapper::Vec(item) => format!("std::vector<{}>", item.cpp_type()),
+            TypeWrapper::Option(item) => format!("std::optional<{}>", item.cpp_type()),
+            TypeWrapper::Bytes(BytesType::Slice) => "hi_ffi::ByteSpan".to_string(),
+            TypeWrapper::Bytes(_) => "std::vector<u8>".to_string(),
+            TypeWrapper::Map { kind, key, value } => {
+                let map_type = match kind {
+                    MapKind::Hash => "std::unordered_map",
+                    MapKind::BTree => "std::map",
+                };
+                format!("{map_type}<{}, {}>", key.cpp_type(), value.cpp_type())
+            }
+            TypeWrapper::Tuple(items) => {
+                let tuple_type = if items.len() == 2 {
+                    "std::pair"
+                } else {
+                    "std::tuple"
+                };
+                let types = items.iter().map(TypeWrapper::cpp_type).collect::<Vec<_>>();
+                format!("{tuple_type}<{}>", types.join(", "))
+            }
+            TypeWrapper::Array { item, len } => format!("std::array<{}, {len}>", item.cpp_type()),
+            TypeWrapper::Callback { args, output, .. } => {
+                let args = args.iter().map(TypeWrapper::cpp_type).collect::<Vec<_>>();
+                let output = output.as_ref().map_or("void".to_string(), |o| o.cpp_type());
+                format!("std::function<{output}({})>", args.join(", "))
+            }
+            TypeWrapper::Trait { name, .. } => format!("std::unique_ptr<{name}>"),
+        }
+    }
+
+    /// Type of the C++ API argument, values that are not trivially copyable are passed by reference
+    pub(super) fn cpp_arg_type(&self) -> String {
+        match self {
+            TypeWrapper::Primitive(_)
+            | TypeWrapper::Int128(_)
+            | TypeWrapper::Char
+            | TypeWrapper::Enum { .. } => self.cpp_type(),
+            TypeWrapper::Str => "std::string_view".to_string(),
+            TypeWrapper::Bytes(BytesType::Slice)
+            | TypeWrapper::Callback { .. }
+            | TypeWrapper::Trait { .. } => self.cpp_type(),
+            _ => format!("const {}&", self.cpp_type()),
+        }
+    }
+
+    /// Converts `value` of `cpp_type` into `c_in_type`.
+    ///
+    /// The result may borrow temporaries, so it has to be consumed within the same full-expression.
+    pub(super) fn cpp_to_ffi(&self, value: impl Display) -> String {
+        match self {
+            TypeWrapper::Primitive(_) => value.to_string(),
+            TypeWrapper::Int128(_) => format!("int128_to_ffi({value})"),
+            TypeWrapper::Char => format!("char_to_ffi({value})"),
+            TypeWrapper::String => format!("string_to_ffi({value})"),
+            TypeWrapper::Str => format!("str_to_ffi({value})"),
+            TypeWrapper::StaticStr => unreachable!("`&'static str` is returned only"),
+            TypeWrapper::Custom(_) => format!("{value}.self_ptr()"), // Rust side makes clone
+            TypeWrapper::Enum { name, .. } => format!("{name}_to_ffi({value})"),
+            TypeWrapper::Vec(item) => match item.as_ref() {
+                // `std::vector<bool>` is packed, so it cannot be passed directly
+                TypeWrapper::Primitive(ty) if ty != "bool" => {
+                    format!("FfiSlice{{{value}.data(), {value}.size(), nullptr}}")
+                }
+                item => item.cpp_slice_arg(value),
+            },
+            TypeWrapper::Option(item) => item.cpp_slice_arg(value),
+            TypeWrapper::Bytes(_) => format!("FfiSlice{{{value}.data(), {value}.size(), nullptr}}"),
+            TypeWrapper::Map {
+                key,
+                value: map_value,
+                ..
+            } => format!(
+                "MapArg<{key_in_type}, {value_in_type}>({value}, [](const {key_type}& key) {{ return {key_to_ffi}; }}, [](const {value_type}& value) {{ return {value_to_ffi}; }}).map()",
+                key_in_type = key.c_in_type(),
+                value_in_type = map_value.c_in_type(),
+                key_type = key.cpp_type(),
+                value_type = map_value.cpp_type(),
+                key_to_ffi = key.cpp_to_ffi("key"),
+                value_to_ffi = map_value.cpp_to_ffi("value"),
+            ),
+            TypeWrapper::Tuple(items) => {
+                let items = items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| item.cpp_to_ffi(format!("std::get<{index}>({value})")))
+                    .collect::<Vec<_>>();
+                format!("{}{{{}}}", self.c_in_type(), items.join(", "))
+            }
+            TypeWrapper::Array { item, .. } => match item.as_ref() {
+                TypeWrapper::Primitive(_) => format!("array_to_ffi<{}>({value})", self.c_in_type()),
+                item => format!(
+                    "array_to_ffi<{in_type}>({value}, [](const {item_type}& item) {{ return {item_to_ffi}; }})",
+                    in_type = self.c_in_type(),
+                    item_type = item.cpp_type(),
+                    item_to_ffi = item.cpp_to_ffi("item"),
+                ),
+            },
+            // the function is moved to the heap and deleted when Rust drops the callback. It is
+            // called from Rust, so exceptions can't unwind through it and terminate the program.
+            TypeWrapper::Callback { args, output, .. } => {
+                let function_type = self.cpp_type();
+                let (args_signatures, args_values): (Vec<_>, Vec<_>) = args
+                    .iter()
+                    .enumerate()
+                    .map(|(index, arg)| {
+                        (
+                            format!(", {} arg{index}", arg.c_out_type()),
+                            arg.cpp_from_ffi(format!("arg{index}")),
+                        )
+                    })
+                    .unzip();
+                let call = format!(
+                    "(*static_cast<{function_type}*>(context))({})",
+                    args_values.join(", ")
+                );
+                let result = match output {
+                    Some(output) => output.cpp_to_ffi(call),
+                    None => call,
+                };
+                format!(
+                    "{c_type}{{[](void* context{args_signatures}) noexcept {{ return {result}; }}, new {function_type}(std::move({value})), [](void* context) {{ delete static_cast<{function_type}*>(context); }}}}",
+                    c_type = self.c_in_type(),
+                    args_signatures = args_signatures.concat(),
+                )
+            }
+            // Rust deletes the implementation when it drops the trait object
+            TypeWrapper::Trait { name, .. } => format!("{name}::into_ffi(std::move({value}))"),
+        }
+    }
+
+    /// Converts `value` of `c_out_type` into `cpp_type`, taking ownership of the Rust value
+    pub(super) fn cpp_from_ffi(&self, value: impl Display) -> String {
+        match self {
+            TypeWrapper::Primitive(_) => value.to_string(),
+            TypeWrapper::Int128(_) => format!("int128_from_ffi({value})"),
+            TypeWrapper::Char => format!("static_cast<char32_t>({value})"),
+            TypeWrapper::String | TypeWrapper::Str => format!("RustString({value}).to_string()"),
+            TypeWrapper::StaticStr => format!("str_from_slice({value})"),
+            TypeWrapper::Custom(ty) | TypeWrapper::Enum { name: ty, .. } => {
+                format!("{ty}({value})")
+            }
+            TypeWrapper::Vec(item) => item.cpp_from_slice("vec_from_slice", value),
+            TypeWrapper::Option(item) => item.cpp_from_slice("optional_from_slice", value),
+            TypeWrapper::Bytes(_) => format!("vec_from_buffer({value})"),
+            TypeWrapper::Map {
+                key,
+                value: map_value,
+                ..
+            } => format!(
+                "map_from_iter<{map_type}, {key_out_type}, {value_out_type}>({value}, []({key_out_type} key) {{ return {key_from_ffi}; }}, []({value_out_type} value) {{ return {value_from_ffi}; }})",
+                map_type = self.cpp_type(),
+                key_out_type = key.c_out_type(),
+                value_out_type = map_value.c_out_type(),
+                key_from_ffi = key.cpp_from_ffi("key"),
+                value_from_ffi = map_value.cpp_from_ffi("value"),
+            ),
+            TypeWrapper::Tuple(items) => {
+                let items = items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| item.cpp_from_ffi(format!("tuple._{index}")))
+                    .collect::<Vec<_>>();
+                format!(
+                    "[]({out_type} tuple) {{ return {tuple_type}({items}); }}({value})",
+                    out_type = self.c_out_type(),
+                    tuple_type = self.cpp_type(),
+                    items = items.join(", "),
+                )
+            }
+            TypeWrapper::Array { item, .. } => match item.as_ref() {
+                TypeWrapper::Primitive(_) => format!("array_from_ffi({value}.items)"),
+                item => format!(
+                    "array_from_ffi<{item_type}>({value}.items, []({out_type} item) {{ return {item_from_ffi}; }})",
+                    item_type = item.cpp_type(),
+                    out_type = item.c_out_type(),
+                    item_from_ffi = item.cpp_from_ffi("item"),
+                ),
+            },
+            TypeWrapper::Callback { .. } | TypeWrapper::Trait { .. } => {
+                unreachable!("Callbacks and trait objects are arguments only")
+            }
+        }
+    }
+
+    /// Converts `value` container with items of this type into `FfiSlice`
+    fn cpp_slice_arg(&self, value: impl Display) -> String {
+        format!(
+            "SliceArg<{in_type}>({value}, [](const {item_type}& item) {{ return {item_to_ffi}; }}).slice()",
+            in_type = self.c_in_type(),
+            item_type = self.cpp_type(),
+            item_to_ffi = self.cpp_to_ffi("item"),
+        )
+    }
+
+    /// Converts `value` slice with items of this type into a container using `from_slice` helper
+    fn cpp_from_slice(&self, from_slice: &str, value: impl Display) -> String {
+        format!(
+            "{from_slice}<{out_type}>({value}, []({out_type} item) {{ return {item_from_ffi}; }})",
+            out_type = self.c_out_type(),
+            item_from_ffi = self.cpp_from_ffi("item"),
+        )
+    }
+
+    /// Headers of the `#[ffi]` items the type refers to and declarations of its tuple structs
+    pub(super) fn cpp_includes(&self) -> String {
+        match self {
+            TypeWrapper::Primitive(_)
+            | TypeWrapper::Int128(_)
+            | TypeWrapper::Char
+            | TypeWrapper::String
+            | TypeWrapper::Str
+            | TypeWrapper::StaticStr
+            | TypeWrapper::Bytes(_) => String::new(),
+            TypeWrapper::Custom(ty)
+            | TypeWrapper::Enum { name: ty, .. }
+            | TypeWrapper::Trait { name: ty, .. } => custom_type_include(ty),
+            TypeWrapper::Vec(item) | TypeWrapper::Option(item) => item.cpp_includes(),
+            TypeWrapper::Map { key, value, .. } => key.cpp_includes() + &value.cpp_includes(),
+            TypeWrapper::Tuple(items) => {
+                items
+                    .iter()
+                    .map(TypeWrapper::cpp_includes)
+                    .collect::<String>()
+                    + &self.c_declarations()
+            }
+            TypeWrapper::Array { item, .. } => item.cpp_includes() + &self.c_declarations(),
+            TypeWrapper::Callback { args, output, .. } => {
+                args.iter()
+                    .chain(output.as_deref())
+                    .map(TypeWrapper::cpp_includes)
+                    .collect::<String>()
+                    + &self.c_declarations()
+            }
+        }
+    }
+}
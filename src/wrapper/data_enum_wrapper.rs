@@ -0,0 +1,183 @@
+use std::fmt::Debug;
+
+use proc_macro2::Ident;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::format_ident;
+use quote::quote;
+use syn::Member;
+
+use super::struct_wrapper::{gen_clone, gen_drop};
+use super::*;
+
+/// Enum with data-carrying variants, passed through FFI as an opaque handle
+pub struct DataEnumWrapper {
+    pub(crate) name: Ident,
+    pub(crate) variants: Vec<DataEnumVariant>,
+    pub(crate) tag_ext_fn_name: String,
+    pub(crate) drop_ext_fn_name: String,
+    pub(crate) clone_ext_fn_name: String,
+    pub(crate) original_item_enum: syn::ItemEnum,
+}
+
+pub struct DataEnumVariant {
+    pub(crate) name: Ident,
+    pub(crate) named_fields: bool, // `Variant { a: i32 }` or `Variant(i32)`
+    pub(crate) fields: Vec<FieldWrapper>, // only getters are generated, tuple fields are named `_0`, `_1`, ...
+    pub(crate) constructor_ext_fn_name: String,
+}
+
+impl Debug for DataEnumWrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataEnumWrapper")
+            .field("name", &self.name)
+            .field("variants", &self.variants)
+            .finish()
+    }
+}
+
+impl Debug for DataEnumVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataEnumVariant")
+            .field("name", &self.name)
+            .field("fields", &self.fields)
+            .finish()
+    }
+}
+
+impl DataEnumVariant {
+    /// Field as it is accessed in Rust - by name or by index for tuple variants
+    fn member(&self, index: usize) -> Member {
+        if self.named_fields {
+            Member::Named(self.fields[index].field_name.clone())
+        } else {
+            Member::Unnamed(index.into())
+        }
+    }
+}
+
+impl From<&DataEnumWrapper> for TokenStream2 {
+    fn from(enum_wrapper: &DataEnumWrapper) -> TokenStream2 {
+        let enum_name = &enum_wrapper.name;
+        let item_enum = &enum_wrapper.original_item_enum;
+
+        let tag = gen_tag(enum_wrapper);
+        let variants = enum_wrapper.variants.iter().map(|variant| {
+            let getters = variant
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(index, field)| gen_variant_getter(variant, index, field, enum_name));
+            let constructor = gen_variant_constructor(variant, enum_name);
+            quote! {
+                #(#getters)*
+                #constructor
+            }
+        });
+        let drop = gen_drop(&enum_wrapper.drop_ext_fn_name, enum_name);
+        let clone = gen_clone(&enum_wrapper.clone_ext_fn_name, enum_name);
+
+        quote! {
+            #item_enum
+            #tag
+            #(#variants)*
+            #drop
+            #clone
+        }
+    }
+}
+
+fn gen_tag(enum_wrapper: &DataEnumWrapper) -> TokenStream2 {
+    let enum_name = &enum_wrapper.name;
+    let tag_ext_fn_name = &enum_wrapper.tag_ext_fn_name;
+    let wrapper_fn_name = format_ident!("{enum_name}_tag");
+    let arms =
+        enum_wrapper
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(tag, DataEnumVariant { name, .. })| {
+                let tag = tag as u32;
+                quote! { #enum_name::#name { .. } => #tag, }
+            });
+
+    quote! {
+        #[doc(hidden)]
+        #[export_name = #tag_ext_fn_name]
+        pub unsafe extern "C" fn #wrapper_fn_name(_self: *mut #enum_name) -> u32 {
+            unsafe {
+                match &*_self {
+                    #(#arms)*
+                }
+            }
+        }
+    }
+}
+
+fn gen_variant_getter(
+    variant: &DataEnumVariant,
+    index: usize,
+    field: &FieldWrapper,
+    enum_name: &Ident,
+) -> TokenStream2 {
+    let Some(Getter {
+        name,
+        extern_fn_name,
+    }) = &field.getter
+    else {
+        return quote! {};
+    };
+
+    let variant_name = &variant.name;
+    let member = variant.member(index);
+    let wrapper_fn_name = format_ident!("{enum_name}_{variant_name}_{name}");
+
+    let return_type = field.wrapper_type.rust_out_type();
+    let result = field.wrapper_type.rust_into_ffi(quote! {value.clone()});
+
+    quote! {
+        #[doc(hidden)]
+        #[allow(unreachable_patterns)]
+        #[export_name = #extern_fn_name]
+        pub unsafe extern "C" fn #wrapper_fn_name(_self: *mut #enum_name) -> #return_type {
+            unsafe {
+                match &*_self {
+                    #enum_name::#variant_name { #member: value, .. } => #result,
+                    _ => panic!("{} is not {}", stringify!(#enum_name), stringify!(#variant_name)),
+                }
+            }
+        }
+    }
+}
+
+fn gen_variant_constructor(variant: &DataEnumVariant, enum_name: &Ident) -> TokenStream2 {
+    let variant_name = &variant.name;
+    let extern_fn_name = &variant.constructor_ext_fn_name;
+    let wrapper_fn_name = format_ident!("{enum_name}_{variant_name}_new");
+
+    let (mut arg_signatures, mut arg_casts, mut members): (Vec<_>, Vec<_>, Vec<_>) =
+        (Vec::new(), Vec::new(), Vec::new());
+    variant
+        .fields
+        .iter()
+        .enumerate()
+        .for_each(|(index, field)| {
+            let field_name = &field.field_name;
+            let member = variant.member(index);
+            members.push(quote! {#member: #field_name});
+            let in_type = field.wrapper_type.rust_in_type();
+            let value = field.wrapper_type.rust_from_ffi(quote! {#field_name});
+            arg_signatures.push(quote! {#field_name: #in_type});
+            arg_casts.push(quote! {
+                let #field_name = unsafe { #value };
+            });
+        });
+
+    quote! {
+        #[doc(hidden)]
+        #[export_name = #extern_fn_name]
+        pub unsafe extern "C" fn #wrapper_fn_name(#(#arg_signatures,)*) -> *mut #enum_name {
+            #(#arg_casts)*
+            Box::into_raw(Box::new(#enum_name::#variant_name { #(#members,)* }))
+        }
+    }
+}
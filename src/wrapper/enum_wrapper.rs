@@ -0,0 +1,72 @@
+use std::fmt::Debug;
+
+use proc_macro2::Ident;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+pub struct EnumWrapper {
+    pub(crate) name: Ident,
+    pub(crate) repr: Ident, // integer type used to pass the enum through FFI
+    pub(crate) variants: Vec<EnumVariant>,
+    pub(crate) original_item_enum: syn::ItemEnum,
+}
+
+#[derive(Debug)]
+pub struct EnumVariant {
+    pub(crate) name: Ident,
+    #[cfg_attr(not(any(feature = "cpp", feature = "swift")), allow(dead_code))]
+    pub(crate) discriminant: Option<String>, // explicit discriminant, implicit ones are left to the target language
+}
+
+impl Debug for EnumWrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EnumWrapper")
+            .field("name", &self.name)
+            .field("repr", &self.repr)
+            .field("variants", &self.variants)
+            .finish()
+    }
+}
+
+impl From<&EnumWrapper> for TokenStream2 {
+    fn from(enum_wrapper: &EnumWrapper) -> TokenStream2 {
+        let enum_name = &enum_wrapper.name;
+        let repr = &enum_wrapper.repr;
+        let item_enum = &enum_wrapper.original_item_enum;
+
+        let (from_repr_arms, into_repr_arms): (Vec<_>, Vec<_>) = enum_wrapper
+            .variants
+            .iter()
+            .map(|EnumVariant { name, .. }| {
+                (
+                    quote! { value if value == #enum_name::#name as #repr => #enum_name::#name, },
+                    quote! { #enum_name::#name => #enum_name::#name as #repr, },
+                )
+            })
+            .unzip();
+
+        quote! {
+            #item_enum
+
+            #[doc(hidden)]
+            impl FfiEnum for #enum_name {
+                type Repr = #repr;
+
+                // the target languages pass only valid values, C++ checks them with `{Enum}_to_ffi`
+                // while Swift enums can't hold other values
+                fn from_repr(value: #repr) -> Self {
+                    match value {
+                        #(#from_repr_arms)*
+                        _ => panic!("Invalid {} value: {}", stringify!(#enum_name), value),
+                    }
+                }
+
+                fn into_repr(&self) -> #repr {
+                    match self {
+                        #(#into_repr_arms)*
+                    }
+                }
+            }
+        }
+    }
+}
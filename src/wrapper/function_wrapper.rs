@@ -1,101 +1,143 @@
 use std::fmt::Debug;
 
 use proc_macro2::TokenStream as TokenStream2;
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, ToTokens};
 
-#[derive(Debug)]
+use super::TypeWrapper;
+
+#[derive(Debug, Clone)]
 pub struct FunctionWrapper {
     pub(crate) name: syn::Ident,
     pub(crate) extern_function_name: String,
+    pub(crate) self_type: Option<syn::Ident>, // type of a method or an associated function
+    pub(crate) receiver: Option<ReceiverWrapper>, // `self` of a method
     pub(crate) args_wrappers: Vec<FunctionArgWrapper>,
     pub(crate) return_wrapper: Option<FunctionReturnWrapper>,
+    pub(crate) error_wrapper: Option<ErrorWrapper>,
+}
+
+impl FunctionWrapper {
+    /// Associated function returning its own type, exposed as a constructor
+    pub(crate) fn is_constructor(&self) -> bool {
+        match (&self.self_type, &self.receiver, &self.return_wrapper) {
+            (
+                Some(self_type),
+                None,
+                Some(FunctionReturnWrapper {
+                    wrapper_type: TypeWrapper::Custom(ty),
+                }),
+            ) => ty.name == *self_type,
+            _ => false,
+        }
+    }
 }
 
 impl From<&FunctionWrapper> for TokenStream2 {
     fn from(function_wrapper: &FunctionWrapper) -> Self {
         let fn_name = &function_wrapper.name;
         let extern_function_name = &function_wrapper.extern_function_name;
-        let wrapper_name = format_ident!("ffi_wrapper_{}", fn_name);
+        let wrapper_name = match &function_wrapper.self_type {
+            Some(self_type) => {
+                format_ident!("{}_{}", self_type.to_string(), fn_name.to_string())
+            }
+            None => format_ident!("ffi_wrapper_{}", fn_name),
+        };
 
         let (mut arg_signatures, mut arg_names, mut arg_casts): (Vec<_>, Vec<_>, Vec<_>) =
             (Vec::new(), Vec::new(), Vec::new());
-        function_wrapper
-            .args_wrappers
-            .iter()
-            .for_each(|arg| match arg {
-                FunctionArgWrapper {
-                    arg_name,
-                    arg_type,
-                    wrapper_type: FunctionArgWrapperType::Primitive,
-                } => {
-                    arg_signatures.push(quote! {#arg_name: #arg_type});
-                    arg_names.push(quote! {#arg_name});
-                }
-                FunctionArgWrapper {
-                    arg_name,
-                    wrapper_type: FunctionArgWrapperType::String,
-                    ..
-                } => {
-                    arg_signatures.push(quote! {#arg_name: *const i8});
-                    arg_names.push(quote! {#arg_name});
-                    arg_casts.push(quote! {
-                        let #arg_name = unsafe { std::ffi::CStr::from_ptr(#arg_name).to_str().unwrap().to_owned() };
-                    });
+        if let (Some(self_type), Some(_)) =
+            (&function_wrapper.self_type, &function_wrapper.receiver)
+        {
+            arg_signatures.push(quote! {_self: *mut #self_type});
+        }
+        function_wrapper.args_wrappers.iter().for_each(
+            |FunctionArgWrapper {
+                 arg_name,
+                 wrapper_type,
+                 ..
+             }| {
+                let in_type = wrapper_type.rust_in_type();
+                arg_signatures.push(quote! {#arg_name: #in_type});
+                arg_names.push(quote! {#arg_name});
+                let value = wrapper_type.rust_from_ffi(quote! {#arg_name});
+                match wrapper_type {
+                    TypeWrapper::Primitive(_) => {}
+                    // the implementation of a trait is taken over without unsafe operations
+                    TypeWrapper::Trait { .. } => arg_casts.push(quote! {
+                        let #arg_name = #value;
+                    }),
+                    _ => arg_casts.push(quote! {
+                        let #arg_name = unsafe { #value };
+                    }),
                 }
-            });
+            },
+        );
 
         let (return_type_sig, result_cast) = match &function_wrapper.return_wrapper {
-            Some(FunctionReturnWrapper {
-                wrapper_type: FunctionReturnWrapperType::Primitive,
-                return_type,
-            }) => (quote! {-> #return_type}, quote! {result}),
-            Some(FunctionReturnWrapper {
-                wrapper_type: FunctionReturnWrapperType::String,
-                ..
-            }) => (
-                quote! {-> *mut String},
-                quote! {
-                    Box::into_raw(Box::new(result))
-                },
-            ),
+            Some(FunctionReturnWrapper { wrapper_type, .. }) => {
+                let out_type = wrapper_type.rust_out_type();
+                let result = wrapper_type.rust_into_ffi(quote! {result});
+                (quote! {-> #out_type}, result)
+            }
             None => (quote! {}, quote! {result}),
         };
 
+        let call = match (&function_wrapper.self_type, &function_wrapper.receiver) {
+            (_, Some(ReceiverWrapper { mutable: true })) => {
+                quote! {unsafe { &mut *_self }.#fn_name(#(#arg_names,)*)}
+            }
+            (_, Some(ReceiverWrapper { mutable: false })) => {
+                quote! {unsafe { &*_self }.#fn_name(#(#arg_names,)*)}
+            }
+            (Some(self_type), None) => quote! {#self_type::#fn_name(#(#arg_names,)*)},
+            (None, None) => quote! {#fn_name(#(#arg_names,)*)},
+        };
+        let body = match &function_wrapper.error_wrapper {
+            Some(error_wrapper) => {
+                arg_signatures.push(quote! {_error: *mut FfiError});
+                error_wrapper.gen_result_match(call, result_cast)
+            }
+            None => quote! {
+                let result = #call;
+                #result_cast
+            },
+        };
+
         quote! {
             #[doc(hidden)]
             #[export_name = #extern_function_name]
             pub unsafe extern "C" fn #wrapper_name(#(#arg_signatures,)*) #return_type_sig {
                 #(#arg_casts)*
-                let result = #fn_name(#(#arg_names,)*);
-                #result_cast
+                #body
             }
         }
     }
 }
 
+/// `&self` or `&mut self` receiver, the instance is passed as the first extern argument
+#[derive(Debug, Clone)]
+pub struct ReceiverWrapper {
+    pub(crate) mutable: bool,
+}
+
+#[derive(Clone)]
 pub struct FunctionArgWrapper {
-    pub(crate) wrapper_type: FunctionArgWrapperType,
+    pub(crate) wrapper_type: TypeWrapper,
     pub(crate) arg_name: syn::Ident,
-    pub(crate) arg_type: syn::Type,
 }
 
 impl Debug for FunctionArgWrapper {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("FunctionArgWrapper")
             .field("arg_name", &self.arg_name)
+            .field("wrapper_type", &self.wrapper_type)
             .finish()
     }
 }
 
-#[derive(Debug)]
-pub enum FunctionArgWrapperType {
-    Primitive,
-    String,
-}
-
+#[derive(Clone)]
 pub struct FunctionReturnWrapper {
-    pub(crate) wrapper_type: FunctionReturnWrapperType,
-    pub(crate) return_type: syn::Type,
+    pub(crate) wrapper_type: TypeWrapper,
 }
 
 impl Debug for FunctionReturnWrapper {
@@ -106,8 +148,54 @@ impl Debug for FunctionReturnWrapper {
     }
 }
 
-#[derive(Debug)]
-pub enum FunctionReturnWrapperType {
-    Primitive,
-    String,
+/// Error of a function returning `Result<T, E>`, it is passed through `FfiError` out-parameter
+#[derive(Clone)]
+pub struct ErrorWrapper {
+    pub(crate) error_type: syn::Type,
+    /// `#[ffi]` enum error, its value is passed together with the message
+    pub(crate) wrapper_type: Option<TypeWrapper>,
+    /// C++ returns the error in `hi_ffi::Expected` rather than throwing it
+    #[cfg_attr(not(feature = "cpp"), allow(dead_code))]
+    pub(crate) cpp_expected: bool,
+}
+
+impl ErrorWrapper {
+    /// Converts `Ok` value of `call` result with `result_cast` or stores the error message and
+    /// returns zeroed value that is ignored by the caller
+    fn gen_result_match(&self, call: TokenStream2, result_cast: TokenStream2) -> TokenStream2 {
+        let store_value = match &self.wrapper_type {
+            Some(TypeWrapper::Enum { .. }) => quote! {
+                (*_error).repr = FfiEnum::into_repr(&error) as i64;
+            },
+            Some(wrapper_type) => {
+                let value = wrapper_type.rust_into_ffi(quote! {error});
+                quote! {
+                    (*_error).variant = #value as *mut std::ffi::c_void;
+                }
+            }
+            None => quote! {},
+        };
+        quote! {
+            match #call {
+                Ok(result) => #result_cast,
+                Err(error) => {
+                    unsafe {
+                        (*_error).message = Box::into_raw(Box::new(error.to_string()));
+                        #store_value
+                        std::mem::zeroed()
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Debug for ErrorWrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ErrorWrapper")
+            .field("error_type", &self.error_type.to_token_stream().to_string())
+            .field("wrapper_type", &self.wrapper_type)
+            .field("cpp_expected", &self.cpp_expected)
+            .finish()
+    }
 }
@@ -0,0 +1,38 @@
+use std::fmt::Debug;
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+use super::{FunctionWrapper, StructWrapper};
+
+pub struct ImplWrapper {
+    pub(crate) struct_wrapper: StructWrapper, // the struct with functions of all impl blocks so far
+    pub(crate) functions: Vec<FunctionWrapper>, // methods and constructors of this impl block
+    pub(crate) original_item_impl: syn::ItemImpl,
+}
+
+impl Debug for ImplWrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ImplWrapper")
+            .field("struct_wrapper", &self.struct_wrapper)
+            .field("functions", &self.functions)
+            .finish()
+    }
+}
+
+impl From<&ImplWrapper> for TokenStream2 {
+    fn from(impl_wrapper: &ImplWrapper) -> TokenStream2 {
+        let mut item_impl = impl_wrapper.original_item_impl.clone();
+        item_impl.items.iter_mut().for_each(|item| {
+            if let syn::ImplItem::Fn(function) = item {
+                function.attrs.retain(|attr| !attr.path().is_ident("ffi"));
+            }
+        });
+        let functions = impl_wrapper.functions.iter().map(TokenStream2::from);
+
+        quote! {
+            #item_impl
+            #(#functions)*
+        }
+    }
+}
@@ -6,12 +6,14 @@ use proc_macro2::TokenStream as TokenStream2;
 use quote::format_ident;
 use quote::quote;
 use quote::ToTokens;
-use syn::Type;
+
+use super::{FunctionWrapper, TypeWrapper};
 
 pub struct StructWrapper {
     pub(crate) name: Ident,
     pub(crate) fields: Vec<FieldWrapper>,
-    pub(crate) default_constructor: Option<DefaultConstructor>, // name of the default constructor if it exists
+    pub(crate) constructors: Vec<FunctionWrapper>, // associated functions returning `Self`
+    pub(crate) methods: Vec<FunctionWrapper>, // methods of all `#[ffi] impl` blocks translated so far
     pub(crate) drop_ext_fn_name: String,
     pub(crate) clone_ext_fn_name: String,
     pub(crate) original_item_struct: syn::ItemStruct,
@@ -22,45 +24,127 @@ impl Debug for StructWrapper {
         f.debug_struct("StructWrapper")
             .field("name", &self.name)
             .field("fields", &self.fields)
+            .field("constructors", &self.constructors)
+            .field("methods", &self.methods)
             .finish()
     }
 }
 
-impl From<&StructWrapper> for TokenStream2 {
-    fn from(struct_wrapper: &StructWrapper) -> TokenStream2 {
-        let class_name = &struct_wrapper.name;
+/// Generic struct exported once for every `#[ffi(instantiate(..))]` instantiation
+pub struct GenericStructWrapper {
+    pub(crate) instances: Vec<StructInstance>,
+    pub(crate) original_item_struct: syn::ItemStruct,
+}
 
-        let fields = struct_wrapper
-            .fields
+/// Instantiation exported as a type alias, `struct_wrapper` is named after the alias and its
+/// fields have the generic parameters replaced
+pub struct StructInstance {
+    pub(crate) instance_type: syn::Type,
+    pub(crate) struct_wrapper: StructWrapper,
+}
+
+impl Debug for GenericStructWrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let instances = self
+            .instances
             .iter()
-            .map(|field| match field.wrapper_type {
-                FieldWrapperType::Primitive => map_primitive_field(field, class_name),
-                FieldWrapperType::String => map_string_field(field, class_name),
-                FieldWrapperType::Custom => map_custom_field(field, class_name),
-            });
+            .map(|instance| &instance.struct_wrapper)
+            .collect::<Vec<_>>();
+        f.debug_struct("GenericStructWrapper")
+            .field("instances", &instances)
+            .finish()
+    }
+}
 
-        let default_constructor =
-            gen_default_constructor(&struct_wrapper.default_constructor, class_name);
-        let drop = gen_drop(&struct_wrapper.drop_ext_fn_name, class_name);
-        let clone = gen_clone(&struct_wrapper.clone_ext_fn_name, class_name);
+impl From<&StructWrapper> for TokenStream2 {
+    fn from(struct_wrapper: &StructWrapper) -> TokenStream2 {
+        let struct_definition = struct_definition(&struct_wrapper.original_item_struct);
+        let bindings = struct_wrapper.bindings();
 
-        let mut item_struct = struct_wrapper.original_item_struct.clone();
-        item_struct.fields.iter_mut().for_each(|field| {
-            field.attrs.retain(|attr| !attr.path().is_ident("ffi"));
-        });
-        let struct_definition = quote! {#item_struct};
+        quote! {
+            #struct_definition
+            #bindings
+        }
+    }
+}
+
+impl From<&GenericStructWrapper> for TokenStream2 {
+    fn from(generic_wrapper: &GenericStructWrapper) -> TokenStream2 {
+        let item_struct = &generic_wrapper.original_item_struct;
+        let struct_definition = struct_definition(item_struct);
+        let vis = &item_struct.vis;
+        let instances = generic_wrapper.instances.iter().map(
+            |StructInstance {
+                 instance_type,
+                 struct_wrapper,
+             }| {
+                let name = &struct_wrapper.name;
+                let bindings = struct_wrapper.bindings();
+                quote! {
+                    #vis type #name = #instance_type;
+                    #bindings
+                }
+            },
+        );
 
         quote! {
             #struct_definition
+            #(#instances)*
+        }
+    }
+}
+
+impl StructWrapper {
+    /// Whether the constructor becomes a C++ constructor and a Swift initializer rather than a
+    /// static factory. `new` without arguments is a factory when the struct has `default` as
+    /// well, as both would have the same signature.
+    #[cfg(any(feature = "cpp", feature = "swift"))]
+    pub(crate) fn is_initializer(&self, constructor: &FunctionWrapper) -> bool {
+        match constructor.name.to_string().as_str() {
+            "default" => true,
+            "new" => {
+                !constructor.args_wrappers.is_empty()
+                    || !self
+                        .constructors
+                        .iter()
+                        .any(|other| other.name == "default")
+            }
+            _ => false,
+        }
+    }
+
+    /// Extern functions of fields, constructors, drop and clone
+    fn bindings(&self) -> TokenStream2 {
+        let class_name = &self.name;
+
+        let fields = self.fields.iter().map(|field| map_field(field, class_name));
+
+        let constructors = self.constructors.iter().map(TokenStream2::from);
+        let drop = gen_drop(&self.drop_ext_fn_name, class_name);
+        let clone = gen_clone(&self.clone_ext_fn_name, class_name);
+
+        quote! {
             #(#fields)*
-            #default_constructor
+            #(#constructors)*
             #drop
             #clone
         }
     }
 }
 
-fn gen_drop(drop_ext_fn_name: impl ToTokens, class_name: impl ToTokens + Display) -> TokenStream2 {
+/// The struct without `#[ffi]` attributes of its fields
+fn struct_definition(item_struct: &syn::ItemStruct) -> TokenStream2 {
+    let mut item_struct = item_struct.clone();
+    item_struct.fields.iter_mut().for_each(|field| {
+        field.attrs.retain(|attr| !attr.path().is_ident("ffi"));
+    });
+    quote! {#item_struct}
+}
+
+pub(crate) fn gen_drop(
+    drop_ext_fn_name: impl ToTokens,
+    class_name: impl ToTokens + Display,
+) -> TokenStream2 {
     let wrapper_fn_name = format_ident!("{class_name}_drop");
     quote! {
         #[doc(hidden)]
@@ -73,7 +157,7 @@ fn gen_drop(drop_ext_fn_name: impl ToTokens, class_name: impl ToTokens + Display
     }
 }
 
-fn gen_clone(
+pub(crate) fn gen_clone(
     clone_ext_fn_name: impl ToTokens,
     class_name: impl ToTokens + Display,
 ) -> TokenStream2 {
@@ -90,129 +174,12 @@ fn gen_clone(
     }
 }
 
-fn gen_default_constructor(
-    constructor_wrapper: &Option<DefaultConstructor>,
-    class_name: impl ToTokens,
-) -> TokenStream2 {
-    if let Some(default_constructor) = constructor_wrapper {
-        let extern_fn_name = &default_constructor.extern_fn_name;
-        let constructor_name = &default_constructor.constructor_name;
-        quote! {
-            #[doc(hidden)]
-            #[export_name = #extern_fn_name]
-            pub unsafe extern "C" fn #constructor_name() -> *mut #class_name {
-                unsafe {
-                    let instance = Box::new(#class_name::default());
-                    Box::into_raw(instance)
-                }
-            }
-        }
-    } else {
-        quote! {}
-    }
-}
-
-fn map_primitive_field(
-    FieldWrapper {
-        field_name,
-        field_type,
-        getter,
-        setter,
-        ..
-    }: &FieldWrapper,
-    class_name: impl ToTokens + Display,
-) -> TokenStream2 {
-    let mut tokens = quote! {};
-    if let Some(Getter {
-        name,
-        extern_fn_name,
-    }) = getter
-    {
-        let wrapper_fn_name = wrapper_fn_name(&class_name, name);
-        tokens.extend(quote! {
-            #[doc(hidden)]
-            #[export_name = #extern_fn_name]
-            pub unsafe extern "C" fn #wrapper_fn_name(_self: *mut #class_name) -> #field_type {
-                unsafe {
-                    (&*_self).#field_name
-                }
-            }
-        })
-    };
-    if let Some(Setter {
-        name,
-        extern_fn_name,
-    }) = setter
-    {
-        let wrapper_fn_name = wrapper_fn_name(&class_name, name);
-        tokens.extend(quote! {
-            #[doc(hidden)]
-            #[export_name = #extern_fn_name]
-            pub unsafe extern "C" fn #wrapper_fn_name(_self: *mut #class_name, value: #field_type) {
-                unsafe {
-                    (&mut *_self).#field_name = value;
-                }
-            }
-        });
-    }
-    tokens
-}
-
-fn map_string_field(
-    FieldWrapper {
-        field_name,
-        getter,
-        setter,
-        ..
-    }: &FieldWrapper,
-    class_name: impl ToTokens + Display,
-) -> TokenStream2 {
-    let mut tokens = quote! {};
-    if let Some(Getter {
-        name,
-        extern_fn_name,
-    }) = getter
-    {
-        let wrapper_fn_name = wrapper_fn_name(&class_name, name);
-        tokens.extend(quote! {
-            #[doc(hidden)]
-            #[export_name = #extern_fn_name]
-            pub unsafe extern "C" fn #wrapper_fn_name(_self: *mut #class_name) -> *mut FfiSlice {
-                unsafe {
-                    Box::into_raw(Box::new(FfiSlice {
-                        ptr: (&*_self).#field_name.as_ptr(),
-                        len: (&*_self).#field_name.len(),
-                    }))
-                }
-            }
-        });
-    };
-    if let Some(Setter {
-        name,
-        extern_fn_name,
-    }) = setter
-    {
-        let wrapper_fn_name = wrapper_fn_name(&class_name, name);
-        tokens.extend(quote! {
-            #[doc(hidden)]
-            #[export_name = #extern_fn_name]
-            pub unsafe extern "C" fn #wrapper_fn_name(_self: *mut #class_name, ptr: *const i8, _len: usize) {
-                unsafe {
-                    let s = std::ffi::CStr::from_ptr(ptr).to_str().unwrap().to_owned();
-                    (&mut *_self).#field_name = s;
-                }
-            }
-        });
-    }
-    tokens
-}
-
-fn map_custom_field(
+fn map_field(
     FieldWrapper {
         field_name,
+        wrapper_type,
         getter,
         setter,
-        field_type,
         ..
     }: &FieldWrapper,
     class_name: impl ToTokens + Display,
@@ -224,12 +191,15 @@ fn map_custom_field(
     }) = getter
     {
         let wrapper_fn_name = wrapper_fn_name(&class_name, name);
+        let out_type = wrapper_type.rust_out_type();
+        let result = wrapper_type.rust_into_ffi(quote! {value});
         tokens.extend(quote! {
             #[doc(hidden)]
             #[export_name = #extern_fn_name]
-            pub unsafe extern "C" fn #wrapper_fn_name(_self: *mut #class_name) -> *mut #field_type {
+            pub unsafe extern "C" fn #wrapper_fn_name(_self: *mut #class_name) -> #out_type {
                 unsafe {
-                    Box::leak(Box::new((&*_self).#field_name.clone()))
+                    let value = (&*_self).#field_name.clone();
+                    #result
                 }
             }
         });
@@ -241,12 +211,14 @@ fn map_custom_field(
     }) = setter
     {
         let wrapper_fn_name = wrapper_fn_name(&class_name, name);
+        let in_type = wrapper_type.rust_in_type();
+        let value = wrapper_type.rust_from_ffi(quote! {value});
         tokens.extend(quote! {
             #[doc(hidden)]
             #[export_name = #extern_fn_name]
-            pub unsafe extern "C" fn #wrapper_fn_name(_self: *mut #class_name, value: *mut #field_type) {
+            pub unsafe extern "C" fn #wrapper_fn_name(_self: *mut #class_name, value: #in_type) {
                 unsafe {
-                    (&mut *_self).#field_name = (*value).clone();
+                    (&mut *_self).#field_name = #value;
                 }
             }
         });
@@ -259,16 +231,9 @@ fn wrapper_fn_name(class_name: impl Display, fn_name: impl Display) -> Ident {
     format_ident!("{class_name}_{fn_name}")
 }
 
-#[derive(Debug)]
-pub struct DefaultConstructor {
-    pub(crate) constructor_name: Ident,
-    pub(crate) extern_fn_name: String,
-}
-
 pub struct FieldWrapper {
     pub(crate) field_name: Ident,
-    pub(crate) field_type: Type,
-    pub(crate) wrapper_type: FieldWrapperType,
+    pub(crate) wrapper_type: TypeWrapper,
     pub(crate) setter: Option<Setter>,
     pub(crate) getter: Option<Getter>,
 }
@@ -291,10 +256,3 @@ impl Debug for FieldWrapper {
             .finish()
     }
 }
-
-#[derive(Debug)]
-pub enum FieldWrapperType {
-    Primitive,
-    String,
-    Custom,
-}
@@ -1,27 +1,78 @@
 use super::*;
 
+use alias_definition::*;
 use class_definition::*;
+use constant_definition::*;
+use data_enum_definition::*;
+use enum_definition::*;
 use function_definition::*;
+use trait_definition::*;
 
+pub mod alias_definition;
 pub mod class_definition;
+pub mod constant_definition;
+pub mod data_enum_definition;
+pub mod enum_definition;
 pub mod function_definition;
+pub mod trait_definition;
+pub mod type_mapping;
 
 pub struct SwiftFiles {
+    pub name: String,
     pub header: String,
     pub source: String,
 }
 
 impl Wrapper {
-    pub fn swift(&self) -> SwiftFiles {
+    /// Files of the item, a generic struct has a class for every instantiation
+    pub fn swift(&self) -> Vec<SwiftFiles> {
+        let files = |header, source| {
+            vec![SwiftFiles {
+                name: self.name(),
+                header,
+                source,
+            }]
+        };
         match &self.parsed {
-            ParsedWrapper::Struct(struct_wrapper) => SwiftFiles {
-                header: gen_class_header(struct_wrapper),
-                source: gen_class_definition(struct_wrapper),
-            },
-            ParsedWrapper::Function(function_wrapper) => SwiftFiles {
-                header: gen_function_header(function_wrapper),
-                source: gen_function_definition(function_wrapper),
-            },
+            ParsedWrapper::Struct(struct_wrapper) => files(
+                gen_class_header(struct_wrapper),
+                gen_class_definition(struct_wrapper),
+            ),
+            ParsedWrapper::GenericStruct(generic_wrapper) => generic_wrapper
+                .instances
+                .iter()
+                .map(|StructInstance { struct_wrapper, .. }| SwiftFiles {
+                    name: struct_wrapper.name.to_string(),
+                    header: gen_class_header(struct_wrapper),
+                    source: gen_class_definition(struct_wrapper),
+                })
+                .collect(),
+            ParsedWrapper::Function(function_wrapper) => files(
+                gen_function_header(function_wrapper),
+                gen_function_definition(function_wrapper),
+            ),
+            ParsedWrapper::Enum(enum_wrapper) => {
+                files(String::new(), gen_enum_definition(enum_wrapper))
+            }
+            ParsedWrapper::DataEnum(enum_wrapper) => files(
+                gen_data_enum_header(enum_wrapper),
+                gen_data_enum_definition(enum_wrapper),
+            ),
+            ParsedWrapper::Impl(impl_wrapper) => files(
+                gen_impl_header(impl_wrapper),
+                gen_class_definition(&impl_wrapper.struct_wrapper),
+            ),
+            ParsedWrapper::Trait(trait_wrapper) => files(
+                gen_trait_header(trait_wrapper),
+                gen_trait_definition(trait_wrapper),
+            ),
+            ParsedWrapper::Alias(alias_wrapper) => {
+                files(String::new(), gen_alias_definition(alias_wrapper))
+            }
+            ParsedWrapper::Constant(constant_wrapper) => files(
+                gen_constant_header(constant_wrapper),
+                gen_constant_definition(constant_wrapper),
+            ),
         }
     }
 }
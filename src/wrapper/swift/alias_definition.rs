@@ -0,0 +1,13 @@
+use super::*;
+
+pub fn gen_alias_definition(alias_wrapper: &AliasWrapper) -> String {
+    let alias_name = &alias_wrapper.name;
+    let swift_type = alias_wrapper.wrapper_type.swift_type();
+
+    format!(
+        r#"@_exported import CFfiModule
+
+public typealias {alias_name} = {swift_type}
+"#
+    )
+}
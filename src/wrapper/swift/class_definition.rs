@@ -1,35 +1,34 @@
 use std::fmt::Display;
 
+use super::function_definition::{function_parts, gen_error_check, FunctionParts};
+use super::type_mapping::ffi_args_declaration;
 use super::*;
-use crate::wrapper::base::{SLICE_DROP_FN_NAME, SLICE_GET_LEN_FN_NAME, SLICE_GET_PTR_FN_NAME};
-use quote::ToTokens;
 
 pub fn gen_class_header(struct_wrapper: &StructWrapper) -> String {
     let destructor_extern_fn = &struct_wrapper.drop_ext_fn_name;
     let getters_and_setters = gen_getters_and_setters_externs(struct_wrapper);
-    let default_constructor = gen_default_constructor_ext(struct_wrapper);
+    let constructors = gen_functions_externs(&struct_wrapper.constructors);
+    let methods = gen_functions_externs(&struct_wrapper.methods);
 
     format!(
         r#"
 void {destructor_extern_fn}(void*);
 {getters_and_setters}
-{default_constructor}
-"#
+{constructors}
+{methods}"#
     )
 }
 
-fn gen_default_constructor_ext(struct_wrapper: &StructWrapper) -> String {
-    if let Some(default_constructor) = struct_wrapper.default_constructor.as_ref() {
-        let default_constructor_ext_fn_name = &default_constructor.extern_fn_name;
+/// Externs of functions from an impl block, the rest of the class header is already declared
+pub fn gen_impl_header(impl_wrapper: &ImplWrapper) -> String {
+    gen_functions_externs(&impl_wrapper.functions)
+}
 
-        format!(
-            r#"
-void* {default_constructor_ext_fn_name}();
-"#
-        )
-    } else {
-        String::new()
-    }
+fn gen_functions_externs(functions: &[FunctionWrapper]) -> String {
+    functions
+        .iter()
+        .map(|function| format!("{}\n", gen_function_header(function)))
+        .collect()
 }
 
 fn gen_getters_and_setters_externs(struct_wrapper: &StructWrapper) -> String {
@@ -41,62 +40,32 @@ fn gen_getters_and_setters_externs(struct_wrapper: &StructWrapper) -> String {
 }
 
 fn gen_getter_and_setter_externs(field: &FieldWrapper) -> String {
-    let field_type = &field.field_type.to_token_stream().to_string();
-    let (getter, setter) = match field {
-        FieldWrapper {
-            wrapper_type: FieldWrapperType::Primitive,
-            setter,
-            getter,
-            ..
-        } => (
-            getter
-                .as_ref()
-                .map(|g| map_primitive_getter_as_extern_fn(g, field_type)),
-            setter
-                .as_ref()
-                .map(|g| map_primitive_setter_as_extern_fn(g, field_type)),
-        ),
-        FieldWrapper {
-            wrapper_type: FieldWrapperType::Custom,
-            ..
-        } => {
-            (None, None) // TODO
-        }
-        FieldWrapper {
-            wrapper_type: FieldWrapperType::String,
-            setter,
-            getter,
-            ..
-        } => (
-            getter.as_ref().map(|g| map_string_getter_as_extern_fn(g)),
-            setter.as_ref().map(|g| map_string_setter_as_extern_fn(g)),
-        ),
-    };
-
+    let getter = field
+        .getter
+        .as_ref()
+        .map(|g| map_getter_as_extern_fn(g, field.wrapper_type.c_out_type()));
+    let setter = field
+        .setter
+        .as_ref()
+        .map(|s| map_setter_as_extern_fn(s, field.wrapper_type.c_in_type()));
+
+    let declarations = field.wrapper_type.c_declarations();
     match (getter, setter) {
-        (Some(getter), Some(setter)) => format!("\n{}\n{}", getter, setter),
-        (Some(getter), None) => format!("\n{}", getter),
-        (None, Some(setter)) => format!("\n{}", setter),
+        (Some(getter), Some(setter)) => format!("{declarations}\n{}\n{}", getter, setter),
+        (Some(getter), None) => format!("{declarations}\n{}", getter),
+        (None, Some(setter)) => format!("{declarations}\n{}", setter),
         (None, None) => String::new(),
     }
 }
 
-fn map_string_getter_as_extern_fn(Getter { extern_fn_name, .. }: &Getter) -> String {
-    format!("void* {extern_fn_name}(void*);")
-}
-
-fn map_string_setter_as_extern_fn(Setter { extern_fn_name, .. }: &Setter) -> String {
-    format!("void {extern_fn_name}(void*, const char*, unsigned int);")
-}
-
-fn map_primitive_getter_as_extern_fn(
+fn map_getter_as_extern_fn(
     Getter { extern_fn_name, .. }: &Getter,
     field_type: impl Display,
 ) -> String {
     format!("{field_type} {extern_fn_name}(void*);")
 }
 
-fn map_primitive_setter_as_extern_fn(
+fn map_setter_as_extern_fn(
     Setter { extern_fn_name, .. }: &Setter,
     field_type: impl Display,
 ) -> String {
@@ -107,7 +76,8 @@ pub fn gen_class_definition(struct_wrapper: &StructWrapper) -> String {
     let class_name = &struct_wrapper.name;
     let destructor_extern_fn = &struct_wrapper.drop_ext_fn_name;
     let props = gen_props(struct_wrapper);
-    let default_constructor = gen_default_constructor(struct_wrapper);
+    let constructors = gen_constructors(struct_wrapper);
+    let methods = gen_methods(&struct_wrapper.methods);
 
     format!(
         r#"
@@ -115,27 +85,78 @@ public class {class_name}: Opaque {{
     deinit {{
         {destructor_extern_fn}(self.rawPtr());
     }}
-{default_constructor}
+{constructors}
 {props}
+{methods}
 }}
 "#
     )
 }
 
-fn gen_default_constructor(struct_wrapper: &StructWrapper) -> String {
-    if let Some(default_constructor) = struct_wrapper.default_constructor.as_ref() {
-        let default_constructor_ext_fn_name = &default_constructor.extern_fn_name;
+fn gen_methods(methods: &[FunctionWrapper]) -> String {
+    methods
+        .iter()
+        .map(|method| {
+            let FunctionParts {
+                signature, body, ..
+            } = function_parts(method, "        ");
+            format!(
+                r#"
+    public {signature}{{{body}
+    }}
+"#
+            )
+        })
+        .collect()
+}
 
-        format!(
-            r#"
-    public convenience init() {{
-        self.init({default_constructor_ext_fn_name}())
+/// Initializers, `new` and `default`, become Swift initializers, other constructors are static
+/// factories
+fn gen_constructors(struct_wrapper: &StructWrapper) -> String {
+    struct_wrapper
+        .constructors
+        .iter()
+        .map(|constructor| {
+            let FunctionParts {
+                signature,
+                body,
+                args_signatures,
+                ffi_args,
+                call,
+            } = function_parts(constructor, "        ");
+            if !struct_wrapper.is_initializer(constructor) {
+                return format!(
+                    r#"
+    public static {signature}{{{body}
     }}
 "#
-        )
-    } else {
-        String::new()
-    }
+                );
+            }
+
+            match &constructor.error_wrapper {
+                Some(error_wrapper) => {
+                    let error_check = gen_error_check(error_wrapper, "        ");
+                    format!(
+                        r#"
+    public convenience init({args_signatures}) throws {{{ffi_args}
+        var ffi_error = FfiError()
+        let ffi_result = {call}
+        {error_check}
+        self.init(ffi_result!)
+    }}
+"#
+                    )
+                }
+                None => format!(
+                    r#"
+    public convenience init({args_signatures}) {{{ffi_args}
+        self.init({call}!)
+    }}
+"#
+                ),
+            }
+        })
+        .collect()
 }
 
 fn gen_props(struct_wrapper: &StructWrapper) -> String {
@@ -143,35 +164,17 @@ fn gen_props(struct_wrapper: &StructWrapper) -> String {
 }
 
 fn gen_property(field: &FieldWrapper) -> String {
-    let (getter, setter) = match field {
-        FieldWrapper {
-            wrapper_type: FieldWrapperType::Primitive,
-            setter,
-            getter,
-            ..
-        } => (
-            getter.as_ref().map(map_primitive_getter),
-            setter.as_ref().map(map_primitive_setter),
-        ),
-        FieldWrapper {
-            wrapper_type: FieldWrapperType::Custom,
-            ..
-        } => {
-            (None, None) // TODO
-        }
-        FieldWrapper {
-            wrapper_type: FieldWrapperType::String,
-            setter,
-            getter,
-            ..
-        } => (
-            getter.as_ref().map(map_string_getter),
-            setter.as_ref().map(map_string_setter),
-        ),
-    };
+    let getter = field
+        .getter
+        .as_ref()
+        .map(|g| map_getter(g, &field.wrapper_type));
+    let setter = field
+        .setter
+        .as_ref()
+        .map(|s| map_setter(s, &field.wrapper_type));
 
     let field_name = &field.field_name;
-    let field_type = &field.field_type.to_token_stream().to_string();
+    let field_type = field.wrapper_type.swift_type();
     match (getter, setter) {
         (Some(getter), Some(setter)) => {
             format!(
@@ -205,46 +208,23 @@ fn gen_property(field: &FieldWrapper) -> String {
     }
 }
 
-fn map_primitive_getter(Getter { extern_fn_name, .. }: &Getter) -> String {
-    format!(
-        r#"
-        get {{
-            return {extern_fn_name}(self.rawPtr())
-        }}"#,
-    )
-}
-
-fn map_primitive_setter(Setter { extern_fn_name, .. }: &Setter) -> String {
-    format!(
-        r#"
-        set {{
-            {extern_fn_name}(self.rawPtr(), newValue)
-        }}"#,
-    )
-}
-
-fn map_string_getter(Getter { extern_fn_name, .. }: &Getter) -> String {
+fn map_getter(Getter { extern_fn_name, .. }: &Getter, wrapper_type: &TypeWrapper) -> String {
+    let result = wrapper_type.swift_from_ffi(format!("{extern_fn_name}(self.rawPtr())"));
     format!(
         r#"
         get {{
-            let slice_ptr = {extern_fn_name}(self.rawPtr())
-            let c_str_ptr = {SLICE_GET_PTR_FN_NAME}(slice_ptr)
-            let c_str_len = {SLICE_GET_LEN_FN_NAME}(slice_ptr)
-            let typed_pointer = c_str_ptr!.assumingMemoryBound(to: UInt8.self)
-            let bytes: UnsafeBufferPointer<UInt8> = UnsafeBufferPointer(start: typed_pointer, count: Int(c_str_len))
-            let result = String(bytes: bytes, encoding: .utf8)!
-            {SLICE_DROP_FN_NAME}(slice_ptr)
-            return result
+            return {result}
         }}"#,
     )
 }
 
-fn map_string_setter(Setter { extern_fn_name, .. }: &Setter) -> String {
+fn map_setter(Setter { extern_fn_name, .. }: &Setter, wrapper_type: &TypeWrapper) -> String {
+    let ffi_args = ffi_args_declaration([wrapper_type], "            ");
+    let value = wrapper_type.swift_to_ffi("newValue");
     format!(
         r#"
-        set {{
-            let str_ptr = newValue.utf8CString.withUnsafeBufferPointer({{ ptr in return UnsafeMutableRawPointer(mutating: ptr.baseAddress!) }})
-            {extern_fn_name}(self.rawPtr(), str_ptr, UInt32(newValue.utf8CString.count))
+        set {{{ffi_args}
+            {extern_fn_name}(self.rawPtr(), {value})
         }}"#,
     )
 }
@@ -0,0 +1,48 @@
+use super::*;
+
+/// Getter extern reading a constant without a literal value
+pub fn gen_constant_header(constant_wrapper: &ConstantWrapper) -> String {
+    match &constant_wrapper.literal {
+        Some(_) => String::new(),
+        None => format!(
+            "{} {}(void);",
+            constant_wrapper.wrapper_type.c_out_type(),
+            constant_wrapper.extern_function_name
+        ),
+    }
+}
+
+/// Global constants are initialized lazily, so other values are read from Rust on first use
+pub fn gen_constant_definition(constant_wrapper: &ConstantWrapper) -> String {
+    let name = &constant_wrapper.name;
+    let wrapper_type = &constant_wrapper.wrapper_type;
+    let swift_type = wrapper_type.swift_type();
+    let value = match &constant_wrapper.literal {
+        Some(literal) => swift_literal(literal),
+        None => wrapper_type.swift_from_ffi(format!("{}()", constant_wrapper.extern_function_name)),
+    };
+
+    format!(
+        r#"@_exported import CFfiModule
+
+public let {name}: {swift_type} = {value}
+"#
+    )
+}
+
+fn swift_literal(literal: &ConstantLiteral) -> String {
+    let escape = |c: char| match c {
+        '"' | '\\' => format!("\\{c}"),
+        c if c.is_control() => format!("\\u{{{:x}}}", c as u32),
+        c => c.to_string(),
+    };
+    match literal {
+        ConstantLiteral::Int(digits) => digits.clone(),
+        ConstantLiteral::Float(value) => format!("{value:?}"),
+        ConstantLiteral::Bool(value) => value.to_string(),
+        ConstantLiteral::Char(value) => format!("\"{}\"", escape(*value)),
+        ConstantLiteral::Str(value) => {
+            format!("\"{}\"", value.chars().map(escape).collect::<String>())
+        }
+    }
+}
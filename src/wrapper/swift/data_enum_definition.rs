@@ -0,0 +1,178 @@
+use super::type_mapping::ffi_args_declaration;
+use super::*;
+
+pub fn gen_data_enum_header(enum_wrapper: &DataEnumWrapper) -> String {
+    let tag_ext_fn_name = &enum_wrapper.tag_ext_fn_name;
+    let drop_ext_fn_name = &enum_wrapper.drop_ext_fn_name;
+    let variants = enum_wrapper
+        .variants
+        .iter()
+        .map(|variant| {
+            let (getters, constructor_args): (String, Vec<_>) = variant
+                .fields
+                .iter()
+                .map(|field| {
+                    let VariantField {
+                        getter_extern_type,
+                        constructor_extern_type,
+                        ..
+                    } = map_variant_field(field);
+                    let declarations = field.wrapper_type.c_declarations();
+                    let getter = match &field.getter {
+                        Some(Getter { extern_fn_name, .. }) => {
+                            format!("{declarations}{getter_extern_type} {extern_fn_name}(void*);\n")
+                        }
+                        None => declarations,
+                    };
+                    (getter, constructor_extern_type)
+                })
+                .unzip();
+            let constructor_ext_fn_name = &variant.constructor_ext_fn_name;
+            let constructor_args = constructor_args.join(", ");
+            format!("{getters}void* {constructor_ext_fn_name}({constructor_args});\n")
+        })
+        .collect::<String>();
+
+    format!(
+        r#"
+u32 {tag_ext_fn_name}(void*);
+void {drop_ext_fn_name}(void*);
+{variants}"#
+    )
+}
+
+pub fn gen_data_enum_definition(enum_wrapper: &DataEnumWrapper) -> String {
+    let enum_name = &enum_wrapper.name;
+    let tag_ext_fn_name = &enum_wrapper.tag_ext_fn_name;
+    let drop_ext_fn_name = &enum_wrapper.drop_ext_fn_name;
+
+    let (mut cases, mut from_ptr_cases, mut to_ptr_cases) =
+        (String::new(), String::new(), String::new());
+    for (tag, variant) in enum_wrapper.variants.iter().enumerate() {
+        let variant_name = &variant.name;
+        let constructor_ext_fn_name = &variant.constructor_ext_fn_name;
+
+        let (mut associated_values, mut getters, mut bindings, mut constructor_args) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for field in &variant.fields {
+            let field_name = &field.field_name;
+            let VariantField {
+                swift_type,
+                getter,
+                constructor_arg,
+                ..
+            } = map_variant_field(field);
+            if variant.named_fields {
+                associated_values.push(format!("{field_name}: {swift_type}"));
+                getters.push(format!("{field_name}: {getter}"));
+            } else {
+                associated_values.push(swift_type);
+                getters.push(getter);
+            }
+            bindings.push(field_name.to_string());
+            constructor_args.push(constructor_arg);
+        }
+        let constructor_args = constructor_args.join(", ");
+
+        if variant.fields.is_empty() {
+            cases.push_str(&format!("    case {variant_name}\n"));
+            from_ptr_cases.push_str(&format!(
+                r#"
+    case {tag}:
+        value = .{variant_name}"#
+            ));
+            to_ptr_cases.push_str(&format!(
+                r#"
+        case .{variant_name}:
+            return {constructor_ext_fn_name}()!"#
+            ));
+        } else {
+            let associated_values = associated_values.join(", ");
+            let getters = getters.join(", ");
+            let bindings = bindings.join(", ");
+            cases.push_str(&format!("    case {variant_name}({associated_values})\n"));
+            from_ptr_cases.push_str(&format!(
+                r#"
+    case {tag}:
+        value = .{variant_name}({getters})"#
+            ));
+            to_ptr_cases.push_str(&format!(
+                r#"
+        case let .{variant_name}({bindings}):
+            return {constructor_ext_fn_name}({constructor_args})!"#
+            ));
+        }
+    }
+
+    let ffi_args = ffi_args_declaration(
+        enum_wrapper
+            .variants
+            .iter()
+            .flat_map(|variant| &variant.fields)
+            .map(|field| &field.wrapper_type),
+        "        ",
+    );
+
+    format!(
+        r#"
+public enum {enum_name} {{
+{cases}}}
+
+extension {enum_name} {{
+    // takes ownership of the Rust value
+    public init(_ ptr: UnsafeMutableRawPointer) {{
+        self = {enum_name}FromRawPtr(ptr)
+    }}
+
+    // creates a new Rust value owned by the caller
+    public func toRawPtr() -> UnsafeMutableRawPointer {{{ffi_args}
+        switch self {{{to_ptr_cases}
+        }}
+    }}
+
+    // creates a new Rust value released together with `args`
+    public func ffiPtr(_ args: FfiArgs) -> UnsafeMutableRawPointer {{
+        let ptr = self.toRawPtr()
+        args.onRelease {{ {drop_ext_fn_name}(ptr) }}
+        return ptr
+    }}
+}}
+
+// declared outside of the enum, where cases named after their payload types don't shadow them
+private func {enum_name}FromRawPtr(_ ptr: UnsafeMutableRawPointer) -> {enum_name} {{
+    let value: {enum_name}
+    switch {tag_ext_fn_name}(ptr) {{{from_ptr_cases}
+    default:
+        fatalError("Invalid {enum_name} tag")
+    }}
+    {drop_ext_fn_name}(ptr)
+    return value
+}}
+"#
+    )
+}
+
+struct VariantField {
+    swift_type: String,
+    getter_extern_type: String,
+    getter: String,
+    constructor_extern_type: String,
+    constructor_arg: String,
+}
+
+fn map_variant_field(field: &FieldWrapper) -> VariantField {
+    let field_name = &field.field_name;
+    let wrapper_type = &field.wrapper_type;
+    let getter_call = match &field.getter {
+        Some(Getter { extern_fn_name, .. }) => format!("{extern_fn_name}(ptr)"),
+        None => unreachable!("Variant fields always have getters"),
+    };
+
+    VariantField {
+        swift_type: wrapper_type.swift_type(),
+        getter_extern_type: wrapper_type.c_out_type(),
+        getter: wrapper_type.swift_from_ffi(getter_call),
+        constructor_extern_type: wrapper_type.c_in_type(),
+        constructor_arg: wrapper_type.swift_to_ffi(field_name),
+    }
+}
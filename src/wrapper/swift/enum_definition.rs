@@ -0,0 +1,21 @@
+use super::*;
+
+pub fn gen_enum_definition(enum_wrapper: &EnumWrapper) -> String {
+    let enum_name = &enum_wrapper.name;
+    let repr = &enum_wrapper.repr;
+    let cases = enum_wrapper
+        .variants
+        .iter()
+        .map(|EnumVariant { name, discriminant }| match discriminant {
+            Some(discriminant) => format!("    case {name} = {discriminant}\n"),
+            None => format!("    case {name}\n"),
+        })
+        .collect::<String>();
+
+    format!(
+        r#"
+public enum {enum_name}: {repr} {{
+{cases}}}
+"#
+    )
+}
@@ -1,146 +1,206 @@
+use super::type_mapping::ffi_args_declaration;
 use crate::wrapper::*;
-use quote::ToTokens;
 
 pub fn gen_function_header(function_wrapper: &FunctionWrapper) -> String {
     let extern_fn_name = &function_wrapper.extern_function_name;
-    let swift_args = function_wrapper
+    let declarations = function_wrapper
         .args_wrappers
         .iter()
-        .map(|arg| match arg {
-            FunctionArgWrapper {
-                arg_name,
-                arg_type,
-                wrapper_type: FunctionArgWrapperType::Primitive,
-            } => {
-                format!("{} {}", arg_type.to_token_stream(), arg_name)
-            }
-            FunctionArgWrapper {
-                arg_name,
-                wrapper_type: FunctionArgWrapperType::String,
-                ..
-            } => {
-                format!("void* {arg_name}")
-            }
-        })
+        .map(|arg| &arg.wrapper_type)
+        .chain(
+            function_wrapper
+                .return_wrapper
+                .as_ref()
+                .map(|return_wrapper| &return_wrapper.wrapper_type),
+        )
+        .map(TypeWrapper::c_declarations)
+        .collect::<String>();
+    let c_args = function_wrapper
+        .receiver
+        .as_ref()
+        .map(|_| "void* self".to_string())
+        .into_iter()
+        .chain(function_wrapper.args_wrappers.iter().map(
+            |FunctionArgWrapper {
+                 arg_name,
+                 wrapper_type,
+             }| format!("{} {arg_name}", wrapper_type.c_in_type()),
+        ))
+        .chain(
+            function_wrapper
+                .error_wrapper
+                .as_ref()
+                .map(|_| "FfiError* error".to_string()),
+        )
         .collect::<Vec<_>>();
 
-    let swift_args = swift_args.join(", ");
+    let c_args = c_args.join(", ");
 
     let ReturnTypes {
         cpp_return_type, ..
-    } = map_return_type(&function_wrapper.return_wrapper);
+    } = map_return_type(
+        &function_wrapper.return_wrapper,
+        &function_wrapper.error_wrapper,
+    );
 
-    format!(r#"{cpp_return_type} {extern_fn_name}({swift_args});"#)
+    format!(r#"{declarations}{cpp_return_type} {extern_fn_name}({c_args});"#)
 }
 
 pub fn gen_function_definition(function_wrapper: &FunctionWrapper) -> String {
-    let fn_name = function_wrapper.name.to_string();
-    let extern_fn_name = &function_wrapper.extern_function_name;
-    let (mut args_signatures, mut args_names, mut args_casts): (Vec<_>, Vec<_>, Vec<_>) =
-        (Vec::new(), Vec::new(), Vec::new());
-    function_wrapper
-        .args_wrappers
-        .iter()
-        .for_each(|arg| match arg {
-            FunctionArgWrapper {
-                arg_name,
-                arg_type,
-                wrapper_type: FunctionArgWrapperType::Primitive,
-            } => {
-                args_signatures.push(format!("_ {}: {}", arg_name, arg_type.to_token_stream()));
-                args_names.push(arg_name.to_string());
-            }
+    let FunctionParts {
+        signature, body, ..
+    } = function_parts(function_wrapper, "    ");
 
-            FunctionArgWrapper {
-                arg_name,
-                wrapper_type: FunctionArgWrapperType::String,
-                ..
-            } => {
-                args_signatures.push(format!("_ {arg_name}: String"));
-                args_names.push(format!("casted_{arg_name}"));
-                args_casts.push(format!(
-                    r#"    let casted_{arg_name} = {arg_name}.utf8CString.withUnsafeBufferPointer({{ ptr in return UnsafeMutableRawPointer(mutating: ptr.baseAddress!) }})"#
-                ));
-            }
-        });
+    format!(
+        r#"@_exported import CFfiModule
+
+public {signature}{{{body}
+}}"#
+    )
+}
+
+/// Parts of the Swift wrapper shared by functions and methods, the instance of a method is passed
+/// to the extern function as `self.rawPtr()`
+pub(super) struct FunctionParts {
+    pub(super) signature: String,
+    pub(super) body: String,
+    pub(super) args_signatures: String,
+    pub(super) ffi_args: String,
+    pub(super) call: String,
+}
+
+pub(super) fn function_parts(function_wrapper: &FunctionWrapper, indent: &str) -> FunctionParts {
+    let fn_name = &function_wrapper.name;
+    let extern_fn_name = &function_wrapper.extern_function_name;
+    let (mut args_signatures, mut args_values): (Vec<_>, Vec<_>) = (Vec::new(), Vec::new());
+    if function_wrapper.receiver.is_some() {
+        args_values.push("self.rawPtr()".to_string());
+    }
+    function_wrapper.args_wrappers.iter().for_each(
+        |FunctionArgWrapper {
+             arg_name,
+             wrapper_type,
+         }| {
+            args_signatures.push(format!("_ {arg_name}: {}", wrapper_type.swift_type()));
+            args_values.push(wrapper_type.swift_to_ffi(arg_name));
+        },
+    );
+    if function_wrapper.error_wrapper.is_some() {
+        args_values.push("&ffi_error".to_string());
+    }
     let args_signatures = args_signatures.join(", ");
-    let args_names = args_names.join(", ");
-    let args_casts = args_casts.join("\n");
+    let args_values = args_values.join(", ");
+    let call = format!("{extern_fn_name}({args_values})");
+    let ffi_args = ffi_args_declaration(
+        function_wrapper
+            .args_wrappers
+            .iter()
+            .map(|arg| &arg.wrapper_type),
+        indent,
+    );
 
     let ReturnTypes {
-        return_type_sig,
-        result_cast,
-        ..
-    } = map_return_type(&function_wrapper.return_wrapper);
+        return_type_sig, ..
+    } = map_return_type(
+        &function_wrapper.return_wrapper,
+        &function_wrapper.error_wrapper,
+    );
+    let body = gen_call_body(
+        call.clone(),
+        &function_wrapper.return_wrapper,
+        &function_wrapper.error_wrapper,
+        indent,
+    );
 
-    let fn_definition = match (return_type_sig, result_cast) {
-        (Some(return_type_sig), Some(result_cast)) => {
-            format!(
-                r#"
-public func {fn_name}({args_signatures}) {return_type_sig}{{
-{args_casts}
-    let result = {extern_fn_name}({args_names})
-{result_cast}
-    return casted_result
-}}"#
-            )
-        }
-        (Some(return_type_sig), None) => {
-            format!(
-                r#"
-public func {fn_name}({args_signatures}) {return_type_sig}{{
-{args_casts}
-    return {extern_fn_name}({args_names})
-}}"#
-            )
-        }
-        (None, _) => {
+    FunctionParts {
+        signature: format!("func {fn_name}({args_signatures}) {return_type_sig}"),
+        body: format!("{ffi_args}\n{indent}{body}"),
+        args_signatures,
+        ffi_args,
+        call,
+    }
+}
+
+/// Body of a function calling `call` and converting its result, errors are thrown as
+/// `RustError` or `RustEnumError` for `#[ffi]` enum errors. Lines after the first one are
+/// indented with `indent`.
+fn gen_call_body(
+    call: String,
+    return_wrapper: &Option<FunctionReturnWrapper>,
+    error_wrapper: &Option<ErrorWrapper>,
+    indent: &str,
+) -> String {
+    let Some(error_wrapper) = error_wrapper else {
+        return match return_wrapper {
+            Some(FunctionReturnWrapper { wrapper_type }) => {
+                format!("return {}", wrapper_type.swift_from_ffi(call))
+            }
+            None => call,
+        };
+    };
+
+    let (call, result) = match return_wrapper {
+        Some(FunctionReturnWrapper { wrapper_type }) => (
+            format!("let ffi_result = {call}"),
             format!(
-                r#"
-public func {fn_name}({args_signatures}) {{
-{args_casts}
-    {extern_fn_name}({args_names})
-}}"#
-            )
-        }
+                "\n{indent}return {}",
+                wrapper_type.swift_from_ffi("ffi_result")
+            ),
+        ),
+        None => (call, String::new()),
     };
+    let error_check = gen_error_check(error_wrapper, indent);
+    format!(
+        r#"var ffi_error = FfiError()
+{indent}{call}
+{indent}{error_check}{result}"#
+    )
+}
 
+/// Throws the error stored in `ffi_error`
+pub(super) fn gen_error_check(
+    ErrorWrapper { wrapper_type, .. }: &ErrorWrapper,
+    indent: &str,
+) -> String {
+    let message = "RustString(message).to_string()";
+    let error = match wrapper_type {
+        Some(TypeWrapper::Enum { name, repr }) => format!(
+            "RustEnumError(message: {message}, variant: {name}(rawValue: {repr}(truncatingIfNeeded: ffi_error.repr))!)"
+        ),
+        Some(wrapper_type) => format!(
+            "RustEnumError(message: {message}, variant: {})",
+            wrapper_type.swift_from_ffi("ffi_error.variant")
+        ),
+        None => format!("RustError(message: {message})"),
+    };
     format!(
-        r#"@_exported import CFfiModule
-{fn_definition}"#
+        r#"if let message = ffi_error.message {{
+{indent}    throw {error}
+{indent}}}"#
     )
 }
 
 struct ReturnTypes {
-    return_type_sig: Option<String>,
+    return_type_sig: String,
     cpp_return_type: String,
-    result_cast: Option<String>,
 }
 
-fn map_return_type(return_wrapper: &Option<FunctionReturnWrapper>) -> ReturnTypes {
+fn map_return_type(
+    return_wrapper: &Option<FunctionReturnWrapper>,
+    error_wrapper: &Option<ErrorWrapper>,
+) -> ReturnTypes {
+    let throws = match error_wrapper {
+        Some(_) => "throws ",
+        None => "",
+    };
     match return_wrapper {
-        Some(FunctionReturnWrapper {
-            wrapper_type: FunctionReturnWrapperType::Primitive,
-            return_type,
-        }) => ReturnTypes {
-            return_type_sig: Some(format!("-> {} ", return_type.to_token_stream())),
-            cpp_return_type: format!("{}", return_type.to_token_stream()),
-            result_cast: None,
-        },
-        Some(FunctionReturnWrapper {
-            wrapper_type: FunctionReturnWrapperType::String,
-            ..
-        }) => ReturnTypes {
-            return_type_sig: Some("-> String ".to_string()),
-            cpp_return_type: "void*".to_string(),
-            result_cast: Some(
-                "    let casted_result = RustString(result!).to_string()".to_string(),
-            ),
+        Some(FunctionReturnWrapper { wrapper_type }) => ReturnTypes {
+            return_type_sig: format!("{throws}-> {} ", wrapper_type.swift_type()),
+            cpp_return_type: wrapper_type.c_out_type(),
         },
         None => ReturnTypes {
-            return_type_sig: None,
+            return_type_sig: throws.to_string(),
             cpp_return_type: "void".to_string(),
-            result_cast: None,
         },
     }
 }
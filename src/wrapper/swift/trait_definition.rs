@@ -0,0 +1,124 @@
+use std::fmt::Display;
+
+use super::type_mapping::ffi_args_declaration;
+use super::*;
+
+/// Declarations of the vtable and the structs of the methods types
+pub fn gen_trait_header(trait_wrapper: &TraitWrapper) -> String {
+    trait_wrapper
+        .methods
+        .iter()
+        .flat_map(|method| {
+            method
+                .args_wrappers
+                .iter()
+                .map(|arg| &arg.wrapper_type)
+                .chain(method.return_wrapper.as_ref().map(|r| &r.wrapper_type))
+        })
+        .map(TypeWrapper::c_declarations)
+        .collect::<String>()
+        + &trait_wrapper.c_declarations()
+}
+
+/// Protocol implemented in Swift. `{Trait}Bridge` retains the implementation passed to Rust and
+/// its vtable functions call the protocol methods.
+pub fn gen_trait_definition(trait_wrapper: &TraitWrapper) -> String {
+    let trait_name = &trait_wrapper.name;
+    let ffi_name = trait_wrapper.ffi_name();
+    let vtable_name = trait_wrapper.vtable_name();
+
+    let requirements = trait_wrapper
+        .methods
+        .iter()
+        .map(requirement)
+        .collect::<String>();
+    let vtable_functions = trait_wrapper
+        .methods
+        .iter()
+        .map(|method| vtable_function(trait_name, method))
+        .collect::<String>();
+
+    format!(
+        r#"@_exported import CFfiModule
+
+public protocol {trait_name} {{
+{requirements}}}
+
+// Keeps an implementation of `{trait_name}` passed to Rust alive until Rust drops it
+public class {trait_name}Bridge {{
+    private var implementation: {trait_name}
+
+    private init(_ implementation: {trait_name}) {{
+        self.implementation = implementation
+    }}
+
+    private static func from(_ context: UnsafeMutableRawPointer?) -> {trait_name}Bridge {{
+        return Unmanaged<{trait_name}Bridge>.fromOpaque(context!).takeUnretainedValue()
+    }}
+
+    public static func toFfi(_ implementation: {trait_name}) -> {ffi_name} {{
+        let context = Unmanaged.passRetained({trait_name}Bridge(implementation)).toOpaque()
+        return {ffi_name}(context: context, vtable: {vtable_name}({vtable_functions}
+            drop: releaseCallbackContext
+        ))
+    }}
+}}
+"#
+    )
+}
+
+/// `&mut self` methods are `mutating`, so the protocol can be implemented by value types
+fn requirement(method: &TraitMethodWrapper) -> String {
+    let name = &method.name;
+    let args = method
+        .args_wrappers
+        .iter()
+        .map(|arg| format!("_ {}: {}", arg.arg_name, arg.wrapper_type.swift_type()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let return_type = method
+        .return_wrapper
+        .as_ref()
+        .map(|r| format!(" -> {}", r.wrapper_type.swift_type()))
+        .unwrap_or_default();
+    let mutating = if method.mutable { "mutating " } else { "" };
+    format!("    {mutating}func {name}({args}){return_type}\n")
+}
+
+/// Closure converting the arguments passed from Rust and calling the method, memory of the
+/// result is kept in `ffi_args` while `setResult` copies it
+fn vtable_function(trait_name: impl Display, method: &TraitMethodWrapper) -> String {
+    let name = &method.name;
+    let (args_names, args_values): (Vec<_>, Vec<_>) = method
+        .args_wrappers
+        .iter()
+        .enumerate()
+        .map(|(index, arg)| {
+            (
+                format!(", arg{index}"),
+                arg.wrapper_type.swift_from_ffi(format!("arg{index}")),
+            )
+        })
+        .unzip();
+    let call = format!(
+        "{trait_name}Bridge.from(context).implementation.{name}({})",
+        args_values.join(", ")
+    );
+    let args_names = args_names.concat();
+
+    match &method.return_wrapper {
+        Some(FunctionReturnWrapper { wrapper_type }) => format!(
+            r#"
+            {name}: {{ context{args_names}, result, setResult in{ffi_args}
+                let value = {call}
+                setResult!(result, {value_to_ffi})
+            }},"#,
+            ffi_args = ffi_args_declaration([wrapper_type], "                "),
+            value_to_ffi = wrapper_type.swift_to_ffi("value"),
+        ),
+        None => format!(
+            r#"
+            {name}: {{ context{args_names} in {call} }},"#
+        ),
+    }
+}
@@ -0,0 +1,245 @@
+use std::fmt::Display;
+
+use super::*;
+
+impl TypeWrapper {
+    /// Type exposed in the Swift API
+    pub(super) fn swift_type(&self) -> String {
+        match self {
+            TypeWrapper::Primitive(ty) | TypeWrapper::Int128(ty) => ty.to_string(),
+            TypeWrapper::Custom(ty) => ty.to_string(),
+            TypeWrapper::Char => "Unicode.Scalar".to_string(),
+            TypeWrapper::String | TypeWrapper::Str | TypeWrapper::StaticStr => "String".to_string(),
+            TypeWrapper::Enum { name, .. } => name.to_string(),
+            TypeWrapper::Vec(item) | TypeWrapper::Array { item, .. } => {
+                format!("[{}]", item.swift_type())
+            }
+            TypeWrapper::Option(item) => format!("{}?", item.swift_type()),
+            TypeWrapper::Bytes(_) => "Data".to_string(),
+            TypeWrapper::Map { key, value, .. } => {
+                format!("[{}: {}]", key.swift_type(), value.swift_type())
+            }
+            TypeWrapper::Tuple(items) => {
+                let types = items
+                    .iter()
+                    .map(TypeWrapper::swift_type)
+                    .collect::<Vec<_>>();
+                format!("({})", types.join(", "))
+            }
+            TypeWrapper::Callback { .. } => format!("@escaping {}", self.swift_closure_type()),
+            TypeWrapper::Trait { name, .. } => name.to_string(),
+        }
+    }
+
+    /// Swift type of a callback closure
+    fn swift_closure_type(&self) -> String {
+        let TypeWrapper::Callback { args, output, .. } = self else {
+            unreachable!("{self:?} is not a callback");
+        };
+        let args = args.iter().map(TypeWrapper::swift_type).collect::<Vec<_>>();
+        let output = output
+            .as_ref()
+            .map_or("Void".to_string(), |o| o.swift_type());
+        format!("({}) -> {output}", args.join(", "))
+    }
+
+    /// Swift view of `c_out_type`
+    fn swift_out_type(&self) -> String {
+        match self {
+            TypeWrapper::Primitive(ty) | TypeWrapper::Int128(ty) => ty.to_string(),
+            TypeWrapper::Char => "u32".to_string(),
+            TypeWrapper::String
+            | TypeWrapper::Str
+            | TypeWrapper::Custom(_)
+            | TypeWrapper::Vec(_)
+            | TypeWrapper::Option(_)
+            | TypeWrapper::Map { .. } => "UnsafeMutableRawPointer?".to_string(),
+            TypeWrapper::StaticStr => "FfiSlice".to_string(),
+            TypeWrapper::Enum { repr, .. } => repr.to_string(),
+            TypeWrapper::Bytes(_) => "FfiBuffer".to_string(),
+            TypeWrapper::Tuple(_) | TypeWrapper::Array { .. } => self.c_out_type(),
+            TypeWrapper::Callback { .. } | TypeWrapper::Trait { .. } => {
+                unreachable!("Callbacks and trait objects are arguments only")
+            }
+        }
+    }
+
+    /// Whether `swift_to_ffi` allocates memory kept in `ffi_args` until the call returns
+    pub(super) fn needs_ffi_args(&self) -> bool {
+        match self {
+            TypeWrapper::Primitive(_)
+            | TypeWrapper::Int128(_)
+            | TypeWrapper::Char
+            | TypeWrapper::Enum { .. }
+            | TypeWrapper::Callback { .. }
+            | TypeWrapper::Trait { .. } => false,
+            TypeWrapper::Tuple(items) => items.iter().any(TypeWrapper::needs_ffi_args),
+            TypeWrapper::Array { item, .. } => item.needs_ffi_args(),
+            _ => true,
+        }
+    }
+
+    /// Converts `value` of `swift_type` into `c_in_type`, temporary memory is owned by `ffi_args`
+    pub(super) fn swift_to_ffi(&self, value: impl Display) -> String {
+        match self {
+            // `i128` and `u128` are the FFI structs
+            TypeWrapper::Primitive(_) | TypeWrapper::Int128(_) => value.to_string(),
+            // `Unicode.Scalar` is valid by construction, its failable initializer rejects surrogates
+            TypeWrapper::Char => format!("{value}.value"),
+            TypeWrapper::String => format!("ffi_args.string({value})"),
+            TypeWrapper::Str => format!("ffi_args.str({value})"),
+            TypeWrapper::StaticStr => unreachable!("`&'static str` is returned only"),
+            TypeWrapper::Custom(_) => format!("{value}.ffiPtr(ffi_args)"), // Rust side makes clone
+            // enums can only hold their cases
+            TypeWrapper::Enum { .. } => format!("{value}.rawValue"),
+            TypeWrapper::Vec(item) => item.swift_slice_arg("slice", value),
+            TypeWrapper::Option(item) => item.swift_slice_arg("optional", value),
+            TypeWrapper::Bytes(_) => format!("ffi_args.bytes({value})"),
+            TypeWrapper::Map {
+                key,
+                value: map_value,
+                ..
+            } => format!(
+                "ffi_args.map({}, {})",
+                key.swift_slice_arg("slice", format!("Array({value}.keys)")),
+                map_value.swift_slice_arg("slice", format!("Array({value}.values)")),
+            ),
+            TypeWrapper::Tuple(items) => {
+                let fields = items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| {
+                        format!(
+                            "_{index}: {}",
+                            item.swift_to_ffi(format!("{value}.{index}"))
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                format!("{}({})", self.c_in_type(), fields.join(", "))
+            }
+            TypeWrapper::Array { item, .. } => match item.as_ref() {
+                TypeWrapper::Primitive(_) => format!("arrayToFfi({value}, {}())", self.c_in_type()),
+                item => format!(
+                    "arrayToFfi({value}.map {{ item in {} }}, {}())",
+                    item.swift_to_ffi("item"),
+                    self.c_in_type()
+                ),
+            },
+            // the closure is retained until Rust drops the callback
+            TypeWrapper::Callback { args, output, .. } => {
+                let (args_names, args_values): (Vec<_>, Vec<_>) = args
+                    .iter()
+                    .enumerate()
+                    .map(|(index, arg)| {
+                        (
+                            format!(", arg{index}"),
+                            arg.swift_from_ffi(format!("arg{index}")),
+                        )
+                    })
+                    .unzip();
+                let call = format!(
+                    "CallbackContext<{}>.callback(context)({})",
+                    self.swift_closure_type(),
+                    args_values.join(", ")
+                );
+                let result = match output {
+                    Some(output) => output.swift_to_ffi(call),
+                    None => call,
+                };
+                format!(
+                    "{c_type}(call: {{ context{args_names} in {result} }}, context: CallbackContext({value}).retain(), drop: releaseCallbackContext)",
+                    c_type = self.c_in_type(),
+                    args_names = args_names.concat(),
+                )
+            }
+            // the implementation is retained until Rust drops the trait object
+            TypeWrapper::Trait { name, .. } => format!("{name}Bridge.toFfi({value})"),
+        }
+    }
+
+    /// Converts `value` of `c_out_type` into `swift_type`, taking ownership of the Rust value
+    pub(super) fn swift_from_ffi(&self, value: impl Display) -> String {
+        match self {
+            TypeWrapper::Primitive(_) | TypeWrapper::Int128(_) => value.to_string(),
+            // `Unicode.Scalar` can't hold a surrogate, so values from Rust are always valid
+            TypeWrapper::Char => format!("Unicode.Scalar({value})!"),
+            TypeWrapper::String | TypeWrapper::Str => format!("RustString({value}!).to_string()"),
+            TypeWrapper::StaticStr => format!("stringFromRustStr({value})"),
+            TypeWrapper::Custom(ty) => format!("{ty}({value}!)"),
+            TypeWrapper::Enum { name, .. } => format!("{name}(rawValue: {value})!"),
+            TypeWrapper::Vec(item) => item.swift_from_slice(value),
+            TypeWrapper::Option(item) => format!("{}.first", item.swift_from_slice(value)),
+            TypeWrapper::Bytes(_) => format!("dataFromRustBuffer({value})"),
+            TypeWrapper::Map {
+                key,
+                value: map_value,
+                ..
+            } => format!(
+                "fromRustMap({value}!, {{ (key: {}) in {} }}, {{ (value: {}) in {} }})",
+                key.swift_out_type(),
+                key.swift_from_ffi("key"),
+                map_value.swift_out_type(),
+                map_value.swift_from_ffi("value"),
+            ),
+            TypeWrapper::Tuple(items) => {
+                let items = items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| item.swift_from_ffi(format!("tuple._{index}")))
+                    .collect::<Vec<_>>();
+                format!(
+                    "{{ (tuple: {}) in ({}) }}({value})",
+                    self.c_out_type(),
+                    items.join(", ")
+                )
+            }
+            TypeWrapper::Array { item, .. } => match item.as_ref() {
+                TypeWrapper::Primitive(ty) => format!("arrayFromFfi({value}, {ty}.self)"),
+                item => format!(
+                    "arrayFromFfi({value}, {}.self).map {{ item in {} }}",
+                    item.swift_out_type(),
+                    item.swift_from_ffi("item")
+                ),
+            },
+            TypeWrapper::Callback { .. } | TypeWrapper::Trait { .. } => {
+                unreachable!("Callbacks and trait objects are arguments only")
+            }
+        }
+    }
+
+    /// Converts `value` container with items of this type into `FfiSlice` using `ffi_args` method
+    fn swift_slice_arg(&self, method: &str, value: impl Display) -> String {
+        match self {
+            TypeWrapper::Primitive(_) => format!("ffi_args.{method}({value})"),
+            item => format!(
+                "ffi_args.{method}({value}.map {{ item in {} }})",
+                item.swift_to_ffi("item")
+            ),
+        }
+    }
+
+    /// Converts `value` slice with items of this type into an array
+    fn swift_from_slice(&self, value: impl Display) -> String {
+        format!(
+            "fromRustSlice({value}!, {{ (item: {}) in {} }})",
+            self.swift_out_type(),
+            self.swift_from_ffi("item")
+        )
+    }
+}
+
+/// Declares `ffi_args` in the generated body, indented with `indent`, if any of the values needs it
+pub(super) fn ffi_args_declaration<'a>(
+    types: impl IntoIterator<Item = &'a TypeWrapper>,
+    indent: &str,
+) -> String {
+    if types.into_iter().any(TypeWrapper::needs_ffi_args) {
+        format!(
+            r#"
+{indent}let ffi_args = FfiArgs()
+{indent}defer {{ ffi_args.release() }}"#
+        )
+    } else {
+        String::new()
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt::Debug;
+
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+
+use super::{FunctionArgWrapper, FunctionReturnWrapper};
+
+/// `#[ffi]` trait implemented in the target language. The implementation is passed to Rust as
+/// `Ffi{Trait}` with a context pointer and a `{Trait}VTable` of functions calling its methods.
+pub struct TraitWrapper {
+    pub(crate) name: Ident,
+    pub(crate) methods: Vec<TraitMethodWrapper>,
+    /// `Send` supertrait, the implementation has to be safe to move to other threads
+    pub(crate) send: bool,
+    /// `Sync` supertrait, the implementation has to be safe to call from several threads at once
+    pub(crate) sync: bool,
+    pub(crate) original_item_trait: syn::ItemTrait,
+}
+
+/// Method called through the vtable. Its arguments are passed from Rust, so they use "out"
+/// representation, while its result is passed back with `set_result` function taking "in"
+/// representation, as it may borrow memory of the target language released once the method
+/// returns.
+pub struct TraitMethodWrapper {
+    pub(crate) name: Ident,
+    pub(crate) mutable: bool,
+    pub(crate) args_wrappers: Vec<FunctionArgWrapper>,
+    pub(crate) return_wrapper: Option<FunctionReturnWrapper>,
+    pub(crate) signature: syn::Signature,
+}
+
+impl Debug for TraitWrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TraitWrapper")
+            .field("name", &self.name)
+            .field("methods", &self.methods)
+            .field("send", &self.send)
+            .field("sync", &self.sync)
+            .finish()
+    }
+}
+
+impl Debug for TraitMethodWrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TraitMethodWrapper")
+            .field("name", &self.name)
+            .field("mutable", &self.mutable)
+            .field("args_wrappers", &self.args_wrappers)
+            .field("return_wrapper", &self.return_wrapper)
+            .finish()
+    }
+}
+
+impl TraitWrapper {
+    pub(crate) fn vtable_name(&self) -> String {
+        format!("{}VTable", self.name)
+    }
+
+    pub(crate) fn ffi_name(&self) -> String {
+        format!("Ffi{}", self.name)
+    }
+
+    /// C declarations of the vtable and the implementation structs
+    #[cfg(any(feature = "cpp", feature = "swift"))]
+    pub(crate) fn c_declarations(&self) -> String {
+        let (vtable_name, ffi_name) = (self.vtable_name(), self.ffi_name());
+        let methods = self
+            .methods
+            .iter()
+            .map(|method| {
+                let args = std::iter::once("void*".to_string())
+                    .chain(
+                        method
+                            .args_wrappers
+                            .iter()
+                            .map(|arg| arg.wrapper_type.c_out_type()),
+                    )
+                    .chain(method.return_wrapper.as_ref().map(|return_wrapper| {
+                        format!(
+                            "void*, void (*)(void*, {})",
+                            return_wrapper.wrapper_type.c_in_type()
+                        )
+                    }))
+                    .collect::<Vec<_>>();
+                format!("    void (*{})({});\n", method.name, args.join(", "))
+            })
+            .collect::<String>();
+
+        format!(
+            r#"
+typedef struct {vtable_name} {{
+{methods}    void (*drop)(void*);
+}} {vtable_name};
+
+typedef struct {ffi_name} {{
+    void* context;
+    {vtable_name} vtable;
+}} {ffi_name};
+"#
+        )
+    }
+}
+
+impl From<&TraitWrapper> for TokenStream2 {
+    fn from(trait_wrapper: &TraitWrapper) -> TokenStream2 {
+        let item_trait = &trait_wrapper.original_item_trait;
+        let trait_name = &trait_wrapper.name;
+        let vtable_name = format_ident!("{}", trait_wrapper.vtable_name());
+        let ffi_name = format_ident!("{}", trait_wrapper.ffi_name());
+
+        let vtable_fields = trait_wrapper.methods.iter().map(|method| {
+            let name = &method.name;
+            let args = method
+                .args_wrappers
+                .iter()
+                .map(|arg| arg.wrapper_type.rust_out_type());
+            let set_result = method.return_wrapper.as_ref().map(|return_wrapper| {
+                let in_type = return_wrapper.wrapper_type.rust_in_type();
+                quote! {
+                    *mut std::ffi::c_void,
+                    unsafe extern "C" fn(*mut std::ffi::c_void, #in_type),
+                }
+            });
+            quote! {
+                pub #name: unsafe extern "C" fn(*mut std::ffi::c_void, #(#args,)* #set_result)
+            }
+        });
+        let methods = trait_wrapper
+            .methods
+            .iter()
+            .map(|method| method_tokens(trait_name, method));
+        // the target language is responsible for making its implementation thread-safe when the
+        // trait requires it
+        let send = trait_wrapper
+            .send
+            .then(|| quote! {unsafe impl Send for #ffi_name {}});
+        let sync = trait_wrapper
+            .sync
+            .then(|| quote! {unsafe impl Sync for #ffi_name {}});
+
+        quote! {
+            #item_trait
+
+            #[doc(hidden)]
+            #[repr(C)]
+            pub struct #vtable_name {
+                #(#vtable_fields,)*
+                pub drop: unsafe extern "C" fn(*mut std::ffi::c_void),
+            }
+
+            #[doc(hidden)]
+            #[repr(C)]
+            pub struct #ffi_name {
+                // implementation of the target language, released with `drop` once Rust drops it
+                pub context: *mut std::ffi::c_void,
+                pub vtable: #vtable_name,
+            }
+
+            #send
+            #sync
+
+            impl Drop for #ffi_name {
+                fn drop(&mut self) {
+                    unsafe { (self.vtable.drop)(self.context) }
+                }
+            }
+
+            impl #trait_name for #ffi_name {
+                #(#methods)*
+            }
+        }
+    }
+}
+
+/// Implementation of the trait method calling the vtable function, the result is written to
+/// a local variable by `set_result`
+fn method_tokens(trait_name: &Ident, method: &TraitMethodWrapper) -> TokenStream2 {
+    let name = &method.name;
+    let receiver = if method.mutable {
+        quote! {&mut self}
+    } else {
+        quote! {&self}
+    };
+    let arg_names = method.args_wrappers.iter().map(|arg| &arg.arg_name);
+    let arg_types = method.signature.inputs.iter().filter_map(|arg| match arg {
+        syn::FnArg::Receiver(_) => None,
+        syn::FnArg::Typed(pat_type) => Some(&pat_type.ty),
+    });
+    let args_into_ffi = method.args_wrappers.iter().map(|arg| {
+        let arg_name = &arg.arg_name;
+        arg.wrapper_type.rust_into_ffi(quote! {#arg_name})
+    });
+    let output = &method.signature.output;
+
+    let (Some(return_wrapper), syn::ReturnType::Type(_, output_type)) =
+        (&method.return_wrapper, output)
+    else {
+        return quote! {
+            fn #name(#receiver, #(#arg_names: #arg_types),*) {
+                unsafe { (self.vtable.#name)(self.context, #(#args_into_ffi),*) }
+            }
+        };
+    };
+    let in_type = return_wrapper.wrapper_type.rust_in_type();
+    let result_from_ffi = return_wrapper.wrapper_type.rust_from_ffi(quote! {value});
+    let missing_result = format!("`{trait_name}::{name}` implementation has not set the result");
+
+    quote! {
+        fn #name(#receiver, #(#arg_names: #arg_types),*) #output {
+            unsafe extern "C" fn set_result(result: *mut std::ffi::c_void, value: #in_type) {
+                unsafe { *(result as *mut Option<#output_type>) = Some(#result_from_ffi) };
+            }
+
+            let mut result: Option<#output_type> = None;
+            unsafe {
+                (self.vtable.#name)(
+                    self.context,
+                    #(#args_into_ffi,)*
+                    &mut result as *mut Option<#output_type> as *mut std::ffi::c_void,
+                    set_result,
+                )
+            };
+            result.expect(#missing_result)
+        }
+    }
+}
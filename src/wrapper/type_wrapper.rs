@@ -0,0 +1,643 @@
+use std::fmt::{Debug, Display};
+
+use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Ident, Literal};
+use quote::{format_ident, quote, ToTokens};
+
+use super::base::int128_ffi_name;
+
+/// Type of a value that crosses the FFI boundary.
+///
+/// Every type has two representations on the `extern "C"` layer:
+/// - "in" - value passed from the target language to Rust, the memory is owned by the caller and
+///   Rust copies what it needs,
+/// - "out" - value passed from Rust to the target language, which takes ownership of it.
+#[derive(Debug, Clone)]
+pub enum TypeWrapper {
+    Primitive(Ident),
+    /// `i128` or `u128` passed as `FfiI128` or `FfiU128` with its 64-bit words
+    Int128(Ident),
+    /// `char` passed as its `u32` code point, which is validated when passed to Rust
+    Char,
+    String,
+    /// `&str` passed without copying as UTF-8 bytes, returned as a copied `String`
+    Str,
+    /// `&'static str` returned without copying as UTF-8 bytes
+    StaticStr,
+    /// `#[ffi]` struct or data-carrying enum passed as an opaque handle
+    Custom(TypeName),
+    /// C-like enum passed as its integer representation
+    Enum {
+        name: TypeName,
+        repr: Ident,
+    },
+    Vec(Box<TypeWrapper>),
+    /// Passed as a slice with zero or one item
+    Option(Box<TypeWrapper>),
+    /// Byte buffer passed without copying, returned as `FfiBuffer` owned by the caller
+    Bytes(BytesType),
+    /// Passed as `FfiMap` with keys and values slices, returned as `FfiMapIter` handle
+    Map {
+        kind: MapKind,
+        key: Box<TypeWrapper>,
+        value: Box<TypeWrapper>,
+    },
+    /// Passed as `repr(C)` struct with a field for every element, supported in functions only
+    Tuple(Vec<TypeWrapper>),
+    /// `[T; N]` of primitives, C-like enums or `#[ffi]` structs passed by value as `FfiArray`
+    Array {
+        item: Box<TypeWrapper>,
+        len: usize,
+    },
+    /// Closure of the target language passed as `FfiCallback`, supported in function arguments
+    /// only. Its arguments are passed from Rust, so they use "out" representation, while its
+    /// result uses "in" representation.
+    Callback {
+        /// `Box<dyn Fn(..)>` rather than `impl Fn(..)`
+        boxed: bool,
+        /// `+ Send` bound, the closure is sent to other threads
+        send: bool,
+        args: Vec<TypeWrapper>,
+        output: Option<Box<TypeWrapper>>,
+    },
+    /// Implementation of `#[ffi]` trait in the target language passed as `Ffi{Trait}`, supported
+    /// in function arguments only
+    Trait {
+        name: TypeName,
+        /// `Box<dyn Trait>` rather than `impl Trait`
+        boxed: bool,
+    },
+}
+
+/// Name of an `#[ffi]` item used by a signature. It is displayed as the name exported to the target
+/// languages, while Rust glue code refers to the item with its path, which may be qualified like
+/// `crate::model::User`.
+#[derive(Clone)]
+pub struct TypeName {
+    pub(crate) name: Ident,
+    pub(crate) path: syn::Path,
+}
+
+impl TypeName {
+    /// Path of the item generated next to this one with `prefix` added to its name
+    pub(crate) fn prefixed(&self, prefix: &str) -> TokenStream2 {
+        let mut path = self.path.clone();
+        if let Some(segment) = path.segments.last_mut() {
+            segment.ident = format_ident!("{prefix}{}", self.name);
+            segment.arguments = syn::PathArguments::None;
+        }
+        quote! {#path}
+    }
+}
+
+impl From<Ident> for TypeName {
+    fn from(name: Ident) -> Self {
+        TypeName {
+            path: name.clone().into(),
+            name,
+        }
+    }
+}
+
+impl Display for TypeName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.name, f)
+    }
+}
+
+impl Debug for TypeName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.path.to_token_stream().to_string(), f)
+    }
+}
+
+impl ToTokens for TypeName {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        self.path.to_tokens(tokens)
+    }
+}
+
+/// Rust type of a byte buffer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BytesType {
+    /// `Vec<u8>`
+    Vec,
+    /// `Box<[u8]>`
+    Boxed,
+    /// `&[u8]` borrowed from the caller, supported in function arguments only
+    Slice,
+}
+
+/// Rust type of a map
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MapKind {
+    /// `HashMap<K, V>`
+    Hash,
+    /// `BTreeMap<K, V>`
+    BTree,
+}
+
+impl MapKind {
+    fn rust_path(&self) -> TokenStream2 {
+        match self {
+            MapKind::Hash => quote! {std::collections::HashMap},
+            MapKind::BTree => quote! {std::collections::BTreeMap},
+        }
+    }
+}
+
+impl TypeWrapper {
+    /// Rust type of the value
+    pub fn rust_type(&self) -> TokenStream2 {
+        match self {
+            TypeWrapper::Primitive(ty) | TypeWrapper::Int128(ty) => quote! {#ty},
+            TypeWrapper::Custom(ty) => quote! {#ty},
+            TypeWrapper::Char => quote! {char},
+            TypeWrapper::String => quote! {String},
+            TypeWrapper::Str => quote! {&str},
+            TypeWrapper::StaticStr => quote! {&'static str},
+            TypeWrapper::Enum { name, .. } => quote! {#name},
+            TypeWrapper::Vec(item) => {
+                let item = item.rust_type();
+                quote! {Vec<#item>}
+            }
+            TypeWrapper::Option(item) => {
+                let item = item.rust_type();
+                quote! {Option<#item>}
+            }
+            TypeWrapper::Bytes(BytesType::Vec) => quote! {Vec<u8>},
+            TypeWrapper::Bytes(BytesType::Boxed) => quote! {Box<[u8]>},
+            TypeWrapper::Bytes(BytesType::Slice) => quote! {&[u8]},
+            TypeWrapper::Map { kind, key, value } => {
+                let (map_type, key, value) = (kind.rust_path(), key.rust_type(), value.rust_type());
+                quote! {#map_type<#key, #value>}
+            }
+            TypeWrapper::Tuple(items) => {
+                let items = items.iter().map(TypeWrapper::rust_type);
+                quote! {(#(#items,)*)}
+            }
+            TypeWrapper::Array { item, len } => {
+                let (item, len) = (item.rust_type(), Literal::usize_unsuffixed(*len));
+                quote! {[#item; #len]}
+            }
+            TypeWrapper::Callback { .. } => unreachable!("Callbacks are not nested"),
+            TypeWrapper::Trait { .. } => unreachable!("Trait objects are not nested"),
+        }
+    }
+
+    /// Type of the value passed from the target language to Rust
+    pub fn rust_in_type(&self) -> TokenStream2 {
+        match self {
+            TypeWrapper::Primitive(ty) => quote! {#ty},
+            TypeWrapper::Int128(_) => self.rust_int128_type(),
+            TypeWrapper::Char => quote! {u32},
+            TypeWrapper::String => quote! {*const std::os::raw::c_char},
+            TypeWrapper::Str => quote! {FfiSlice},
+            TypeWrapper::StaticStr => unreachable!("`&'static str` is returned only"),
+            TypeWrapper::Custom(ty) => quote! {*const #ty},
+            TypeWrapper::Enum { repr, .. } => quote! {#repr},
+            TypeWrapper::Vec(_) | TypeWrapper::Option(_) | TypeWrapper::Bytes(_) => {
+                quote! {FfiSlice}
+            }
+            TypeWrapper::Map { .. } => quote! {FfiMap},
+            TypeWrapper::Tuple(items) => {
+                let name = format_ident!("FfiTuple{}", items.len());
+                let types = items.iter().map(TypeWrapper::rust_in_type);
+                quote! {#name<#(#types),*>}
+            }
+            TypeWrapper::Array { item, len } => {
+                let (item, len) = (item.rust_in_type(), Literal::usize_unsuffixed(*len));
+                quote! {FfiArray<#item, #len>}
+            }
+            TypeWrapper::Callback { args, output, .. } => {
+                let args = args.iter().map(TypeWrapper::rust_out_type);
+                let output = output.as_ref().map(|output| {
+                    let output = output.rust_in_type();
+                    quote! {-> #output}
+                });
+                quote! {
+                    FfiCallback<unsafe extern "C" fn(*mut std::ffi::c_void, #(#args),*) #output>
+                }
+            }
+            TypeWrapper::Trait { name, .. } => name.prefixed("Ffi"),
+        }
+    }
+
+    /// Type of the value passed from Rust to the target language
+    pub fn rust_out_type(&self) -> TokenStream2 {
+        match self {
+            TypeWrapper::Primitive(ty) => quote! {#ty},
+            TypeWrapper::Int128(_) => self.rust_int128_type(),
+            TypeWrapper::Char => quote! {u32},
+            TypeWrapper::String | TypeWrapper::Str => quote! {*mut String},
+            TypeWrapper::StaticStr => quote! {FfiSlice},
+            TypeWrapper::Custom(ty) => quote! {*mut #ty},
+            TypeWrapper::Enum { repr, .. } => quote! {#repr},
+            TypeWrapper::Vec(_) | TypeWrapper::Option(_) => quote! {*mut FfiSlice},
+            TypeWrapper::Bytes(_) => quote! {FfiBuffer},
+            TypeWrapper::Map { .. } => quote! {*mut FfiMapIter},
+            TypeWrapper::Tuple(items) => {
+                let name = format_ident!("FfiTuple{}", items.len());
+                let types = items.iter().map(TypeWrapper::rust_out_type);
+                quote! {#name<#(#types),*>}
+            }
+            TypeWrapper::Array { item, len } => {
+                let (item, len) = (item.rust_out_type(), Literal::usize_unsuffixed(*len));
+                quote! {FfiArray<#item, #len>}
+            }
+            TypeWrapper::Callback { .. } | TypeWrapper::Trait { .. } => {
+                unreachable!("Callbacks and trait objects are arguments only")
+            }
+        }
+    }
+
+    /// Converts `value` of `rust_in_type` into an owned Rust value, has to be used in unsafe context
+    pub fn rust_from_ffi(&self, value: TokenStream2) -> TokenStream2 {
+        match self {
+            TypeWrapper::Primitive(_) => value,
+            TypeWrapper::Int128(ty) => quote! {#ty::from(#value)},
+            // surrogates and values above U+10FFFF are not valid `char`s, C++ rejects them with
+            // `char_to_ffi` while Swift `Unicode.Scalar` can't hold them
+            TypeWrapper::Char => quote! {char::from_u32(#value).expect("Invalid char value")},
+            TypeWrapper::String => {
+                quote! {std::ffi::CStr::from_ptr(#value).to_str().unwrap().to_owned()}
+            }
+            TypeWrapper::Str => {
+                quote! {std::str::from_utf8((#value).as_slice::<u8>()).expect("Invalid UTF-8 string")}
+            }
+            TypeWrapper::StaticStr => unreachable!("`&'static str` is returned only"),
+            TypeWrapper::Custom(_) => quote! {(*#value).clone()},
+            TypeWrapper::Enum { name, .. } => quote! {<#name as FfiEnum>::from_repr(#value)},
+            TypeWrapper::Vec(item) if matches!(**item, TypeWrapper::Primitive(_)) => {
+                let item_type = item.rust_in_type();
+                quote! {(#value).as_slice::<#item_type>().to_vec()}
+            }
+            TypeWrapper::Vec(item) => {
+                let item_type = item.rust_in_type();
+                let item_from_ffi = item.rust_from_ffi(quote! {*item});
+                quote! {
+                    (#value)
+                        .as_slice::<#item_type>()
+                        .iter()
+                        .map(|item| #item_from_ffi)
+                        .collect::<Vec<_>>()
+                }
+            }
+            TypeWrapper::Option(item) => {
+                let item_type = item.rust_in_type();
+                let item_from_ffi = item.rust_from_ffi(quote! {*item});
+                quote! {
+                    (#value)
+                        .as_slice::<#item_type>()
+                        .first()
+                        .map(|item| #item_from_ffi)
+                }
+            }
+            TypeWrapper::Bytes(BytesType::Vec) => quote! {(#value).as_slice::<u8>().to_vec()},
+            TypeWrapper::Bytes(BytesType::Boxed) => {
+                quote! {Box::<[u8]>::from((#value).as_slice::<u8>())}
+            }
+            TypeWrapper::Bytes(BytesType::Slice) => quote! {(#value).as_slice::<u8>()},
+            TypeWrapper::Map {
+                kind,
+                key,
+                value: map_value,
+            } => {
+                let map_type = kind.rust_path();
+                let (key_type, value_type) = (key.rust_in_type(), map_value.rust_in_type());
+                let key_from_ffi = key.rust_from_ffi(quote! {*key});
+                let value_from_ffi = map_value.rust_from_ffi(quote! {*value});
+                quote! {
+                    (#value)
+                        .keys
+                        .as_slice::<#key_type>()
+                        .iter()
+                        .zip((#value).values.as_slice::<#value_type>())
+                        .map(|(key, value)| (#key_from_ffi, #value_from_ffi))
+                        .collect::<#map_type<_, _>>()
+                }
+            }
+            TypeWrapper::Tuple(items) => {
+                let items = items.iter().enumerate().map(|(index, item)| {
+                    let field = format_ident!("_{index}");
+                    item.rust_from_ffi(quote! {tuple.#field})
+                });
+                quote! {{
+                    let tuple = #value;
+                    (#(#items,)*)
+                }}
+            }
+            TypeWrapper::Array { item, .. } if matches!(**item, TypeWrapper::Primitive(_)) => {
+                quote! {(#value).items}
+            }
+            TypeWrapper::Array { item, .. } => {
+                let item_from_ffi = item.rust_from_ffi(quote! {item});
+                quote! {(#value).items.map(|item| #item_from_ffi)}
+            }
+            // the closure owns the callback, so the target language closure is released with it
+            TypeWrapper::Callback {
+                boxed,
+                send,
+                args,
+                output,
+            } => {
+                let (arg_names, arg_types): (Vec<_>, Vec<_>) = (0..args.len())
+                    .map(|index| format_ident!("arg{index}"))
+                    .zip(args.iter().map(TypeWrapper::rust_type))
+                    .unzip();
+                let args_into_ffi = arg_names
+                    .iter()
+                    .zip(args)
+                    .map(|(arg_name, arg)| arg.rust_into_ffi(quote! {#arg_name}));
+                let result = match output {
+                    Some(output) => output.rust_from_ffi(quote! {result}),
+                    None => quote! {result},
+                };
+                let (callback, unwrap_callback) = if *send {
+                    (
+                        quote! {FfiSendCallback(#value)},
+                        quote! {let callback = &callback.0;},
+                    )
+                } else {
+                    (value, quote! {})
+                };
+                let closure = quote! {
+                    move |#(#arg_names: #arg_types),*| {
+                        // captures the whole callback rather than its fields, so it is dropped
+                        // together with the closure
+                        let callback = &callback;
+                        #unwrap_callback
+                        let result = (callback.call)(callback.context, #(#args_into_ffi),*);
+                        #result
+                    }
+                };
+                let closure = if *boxed {
+                    quote! {Box::new(#closure)}
+                } else {
+                    closure
+                };
+                quote! {{
+                    let callback = #callback;
+                    #closure
+                }}
+            }
+            TypeWrapper::Trait { boxed: true, .. } => quote! {Box::new(#value)},
+            TypeWrapper::Trait { boxed: false, .. } => value,
+        }
+    }
+
+    /// Converts owned Rust `value` into `rust_out_type`
+    pub fn rust_into_ffi(&self, value: TokenStream2) -> TokenStream2 {
+        match self {
+            TypeWrapper::Primitive(_) => value,
+            TypeWrapper::Int128(_) => {
+                let ffi_type = self.rust_int128_type();
+                quote! {#ffi_type::from(#value)}
+            }
+            TypeWrapper::Char => quote! {u32::from(#value)},
+            TypeWrapper::String | TypeWrapper::Custom(_) => {
+                quote! {Box::into_raw(Box::new(#value))}
+            }
+            TypeWrapper::Str => quote! {Box::into_raw(Box::new((#value).to_string()))},
+            TypeWrapper::StaticStr => quote! {
+                FfiSlice {
+                    ptr: (#value).as_ptr(),
+                    len: (#value).len(),
+                    drop: None,
+                }
+            },
+            TypeWrapper::Enum { .. } => quote! {FfiEnum::into_repr(&#value)},
+            TypeWrapper::Vec(item) if matches!(**item, TypeWrapper::Primitive(_)) => {
+                quote! {FfiSlice::from_vec(#value)}
+            }
+            TypeWrapper::Vec(item) | TypeWrapper::Option(item) => {
+                let item_into_ffi = item.rust_into_ffi(quote! {item});
+                quote! {
+                    FfiSlice::from_vec((#value).into_iter().map(|item| #item_into_ffi).collect::<Vec<_>>())
+                }
+            }
+            TypeWrapper::Bytes(BytesType::Slice) => {
+                unreachable!("`&[u8]` is supported in arguments only")
+            }
+            TypeWrapper::Bytes(_) => quote! {FfiBuffer::new(#value)},
+            TypeWrapper::Map {
+                key,
+                value: map_value,
+                ..
+            } => {
+                let key_into_ffi = key.rust_into_ffi(quote! {key});
+                let value_into_ffi = map_value.rust_into_ffi(quote! {value});
+                quote! {{
+                    let map = #value;
+                    FfiMapIter::new(
+                        map.len(),
+                        map.into_iter().map(|(key, value)| (#key_into_ffi, #value_into_ffi)),
+                    )
+                }}
+            }
+            TypeWrapper::Tuple(items) => {
+                let name = format_ident!("FfiTuple{}", items.len());
+                let fields = items.iter().enumerate().map(|(index, item)| {
+                    let field = format_ident!("_{index}");
+                    let index = syn::Index::from(index);
+                    let item_into_ffi = item.rust_into_ffi(quote! {tuple.#index});
+                    quote! {#field: #item_into_ffi}
+                });
+                quote! {{
+                    let tuple = #value;
+                    #name { #(#fields,)* }
+                }}
+            }
+            TypeWrapper::Array { item, .. } if matches!(**item, TypeWrapper::Primitive(_)) => {
+                quote! {FfiArray { items: #value }}
+            }
+            TypeWrapper::Array { item, .. } => {
+                let item_into_ffi = item.rust_into_ffi(quote! {item});
+                quote! {FfiArray { items: (#value).map(|item| #item_into_ffi) }}
+            }
+            TypeWrapper::Callback { .. } | TypeWrapper::Trait { .. } => {
+                unreachable!("Callbacks and trait objects are arguments only")
+            }
+        }
+    }
+
+    /// `FfiI128` or `FfiU128` struct of an `Int128`
+    fn rust_int128_type(&self) -> TokenStream2 {
+        let TypeWrapper::Int128(ty) = self else {
+            unreachable!("{self:?} is not a 128-bit integer");
+        };
+        let name = format_ident!("{}", int128_ffi_name(ty));
+        quote! {#name}
+    }
+
+    /// C type matching `rust_in_type`
+    #[cfg(any(feature = "cpp", feature = "swift"))]
+    pub fn c_in_type(&self) -> String {
+        match self {
+            TypeWrapper::Primitive(ty) => ty.to_string(),
+            TypeWrapper::Int128(ty) => int128_ffi_name(ty),
+            TypeWrapper::Char => "u32".to_string(),
+            TypeWrapper::String => "const char*".to_string(),
+            TypeWrapper::Str => "FfiSlice".to_string(),
+            TypeWrapper::StaticStr => unreachable!("`&'static str` is returned only"),
+            TypeWrapper::Custom(_) => "void*".to_string(),
+            TypeWrapper::Enum { repr, .. } => repr.to_string(),
+            TypeWrapper::Vec(_) | TypeWrapper::Option(_) | TypeWrapper::Bytes(_) => {
+                "FfiSlice".to_string()
+            }
+            TypeWrapper::Map { .. } => "FfiMap".to_string(),
+            TypeWrapper::Tuple(items) => c_tuple_name(items.iter().map(TypeWrapper::c_in_type)),
+            TypeWrapper::Array { item, len } => c_array_name(&item.c_in_type(), *len),
+            TypeWrapper::Callback { args, output, .. } => {
+                let output = output
+                    .as_ref()
+                    .map_or("void".to_string(), |o| o.c_in_type());
+                c_struct_name(
+                    "FfiCallback",
+                    std::iter::once(output).chain(args.iter().map(TypeWrapper::c_out_type)),
+                )
+            }
+            TypeWrapper::Trait { name, .. } => format!("Ffi{name}"),
+        }
+    }
+
+    /// C type matching `rust_out_type`
+    #[cfg(any(feature = "cpp", feature = "swift"))]
+    pub fn c_out_type(&self) -> String {
+        match self {
+            TypeWrapper::Primitive(ty) => ty.to_string(),
+            TypeWrapper::Int128(ty) => int128_ffi_name(ty),
+            TypeWrapper::Char => "u32".to_string(),
+            TypeWrapper::String
+            | TypeWrapper::Str
+            | TypeWrapper::Custom(_)
+            | TypeWrapper::Vec(_)
+            | TypeWrapper::Option(_)
+            | TypeWrapper::Map { .. } => "void*".to_string(),
+            TypeWrapper::StaticStr => "FfiSlice".to_string(),
+            TypeWrapper::Enum { repr, .. } => repr.to_string(),
+            TypeWrapper::Bytes(_) => "FfiBuffer".to_string(),
+            TypeWrapper::Tuple(items) => c_tuple_name(items.iter().map(TypeWrapper::c_out_type)),
+            TypeWrapper::Array { item, len } => c_array_name(&item.c_out_type(), *len),
+            TypeWrapper::Callback { .. } | TypeWrapper::Trait { .. } => {
+                unreachable!("Callbacks and trait objects are arguments only")
+            }
+        }
+    }
+
+    /// C declarations of the tuple, array and callback structs used by `c_in_type` and
+    /// `c_out_type`, they are guarded as every header using the type repeats them
+    #[cfg(any(feature = "cpp", feature = "swift"))]
+    pub fn c_declarations(&self) -> String {
+        match self {
+            TypeWrapper::Callback { args, output, .. } => {
+                c_callback_declaration(&self.c_in_type(), args, output.as_deref())
+            }
+            TypeWrapper::Tuple(items) => {
+                let in_types = items.iter().map(TypeWrapper::c_in_type).collect::<Vec<_>>();
+                let out_types = items
+                    .iter()
+                    .map(TypeWrapper::c_out_type)
+                    .collect::<Vec<_>>();
+                let mut declarations = items
+                    .iter()
+                    .map(TypeWrapper::c_declarations)
+                    .collect::<String>();
+                declarations += &c_tuple_declaration(&self.c_in_type(), &in_types);
+                if out_types != in_types {
+                    declarations += &c_tuple_declaration(&self.c_out_type(), &out_types);
+                }
+                declarations
+            }
+            TypeWrapper::Array { item, len } => {
+                let (in_type, out_type) = (item.c_in_type(), item.c_out_type());
+                let mut declarations = c_array_declaration(&self.c_in_type(), &in_type, *len);
+                if out_type != in_type {
+                    declarations += &c_array_declaration(&self.c_out_type(), &out_type, *len);
+                }
+                declarations
+            }
+            _ => String::new(),
+        }
+    }
+}
+
+/// Name of the C struct matching `FfiTuple{N}` with fields of `types`
+#[cfg(any(feature = "cpp", feature = "swift"))]
+fn c_tuple_name(types: impl Iterator<Item = String>) -> String {
+    c_struct_name("FfiTuple", types)
+}
+
+/// Name of the C struct matching `FfiArray<T, N>` with `len` items of `item_type`
+#[cfg(any(feature = "cpp", feature = "swift"))]
+fn c_array_name(item_type: &str, len: usize) -> String {
+    c_struct_name(
+        "FfiArray",
+        [item_type.to_string(), len.to_string()].into_iter(),
+    )
+}
+
+/// Name of the C struct matching generic Rust struct `name` instantiated with `types`
+#[cfg(any(feature = "cpp", feature = "swift"))]
+fn c_struct_name(name: &str, types: impl Iterator<Item = String>) -> String {
+    types.fold(name.to_string(), |name, ty| {
+        format!("{name}_{}", ty.replace(' ', "_").replace('*', "_ptr"))
+    })
+}
+
+#[cfg(any(feature = "cpp", feature = "swift"))]
+fn c_callback_declaration(
+    name: &str,
+    args: &[TypeWrapper],
+    output: Option<&TypeWrapper>,
+) -> String {
+    let output = output.map_or("void".to_string(), TypeWrapper::c_in_type);
+    let args = std::iter::once("void*".to_string())
+        .chain(args.iter().map(TypeWrapper::c_out_type))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        r#"
+#ifndef {name}_DEFINED
+#define {name}_DEFINED
+typedef struct {name} {{
+    {output} (*call)({args});
+    void* context;
+    void (*drop)(void*);
+}} {name};
+#endif
+"#
+    )
+}
+
+#[cfg(any(feature = "cpp", feature = "swift"))]
+fn c_tuple_declaration(name: &str, types: &[String]) -> String {
+    let fields = types
+        .iter()
+        .enumerate()
+        .map(|(index, ty)| format!("    {ty} _{index};\n"))
+        .collect::<String>();
+    format!(
+        r#"
+#ifndef {name}_DEFINED
+#define {name}_DEFINED
+typedef struct {name} {{
+{fields}}} {name};
+#endif
+"#
+    )
+}
+
+#[cfg(any(feature = "cpp", feature = "swift"))]
+fn c_array_declaration(name: &str, item_type: &str, len: usize) -> String {
+    format!(
+        r#"
+#ifndef {name}_DEFINED
+#define {name}_DEFINED
+typedef struct {name} {{
+    {item_type} items[{len}];
+}} {name};
+#endif
+"#
+    )
+}
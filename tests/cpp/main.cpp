@@ -5,9 +5,137 @@
 #include "function_return_primitive.h"
 #include "function_return_string.h"
 #include "combo_function.h"
+#include "function_with_enum_arg.h"
+#include "function_return_enum.h"
+#include "function_size_enum_value.h"
+#include "function_sum_vec.h"
+#include "function_return_vec.h"
+#include "function_negate_vec.h"
+#include "function_join_strings.h"
+#include "function_split_string.h"
+#include "function_sum_structs.h"
+#include "function_return_structs.h"
+#include "function_count_enums.h"
+#include "function_item_value.h"
+#include "TestItem.h"
+#include "function_struct_value.h"
+#include "function_optional_len.h"
+#include "function_find_struct.h"
+#include "function_parse_i32.h"
+#include "function_check_value.h"
+#include "function_parse_u32.h"
+#include "function_scale_point.h"
+#include "function_make_struct.h"
+#include "function_shape_area.h"
+#include "function_str_len.h"
+#include "function_level_name.h"
+#include "function_reverse_bytes.h"
+#include "function_checksum.h"
+#include "function_repeat_byte.h"
+#include "function_parse_bytes.h"
+#include "function_count_words.h"
+#include "function_sum_scores.h"
+#include "function_index_structs.h"
+#include "function_sum_struct_map.h"
+#include "function_bounds.h"
+#include "function_split_once.h"
+#include "function_repeat_pair.h"
+#include "function_tag_struct.h"
+#include "function_count_progress.h"
+#include "function_filter_words.h"
+#include "function_visit_structs.h"
+#include "function_set_listener.h"
+#include "function_call_on_thread.h"
+#include "function_notify_listener.h"
+#include "function_clear_listener.h"
+#include "function_fill_storage.h"
+#include "function_storage_summary.h"
+#include "function_set_storage.h"
+#include "function_storage_scale.h"
+#include "function_clear_storage.h"
+#include "function_format_values.h"
+#include "function_page_numbers.h"
+#include "function_page_total.h"
+#include "function_reverse_color.h"
+#include "function_identity_matrix.h"
+#include "function_swap_corners.h"
+#include "function_next_char.h"
+#include "function_chars.h"
+#include "function_count_chars.h"
+#include "function_map_chars.h"
+#include "function_add_i128.h"
+#include "function_u128_words.h"
+#include "function_sum_i128.h"
+#include "function_offset_index.h"
+#include "function_word_lengths.h"
+#include "function_format_address.h"
+#include "function_street_addresses.h"
+#include "function_checked_enum.h"
+#include "function_next_user_id.h"
+#include "function_checked_user_id.h"
+#include "function_address_numbers.h"
+#include "function_location_street.h"
 #include "TestStruct.h"
+#include "TestPoint.h"
+#include "TestCounter.h"
+#include "function_parse_u8.h"
+#include "TestTransform.h"
+#include "TestUserId.h"
+#include "TestAddresses.h"
+#include "TestLocation.h"
+#include "TEST_MAX_BATCH.h"
+#include "TEST_OFFSET.h"
+#include "TEST_MAX_ID.h"
+#include "TEST_MIN_DELTA.h"
+#include "TEST_RATIO.h"
+#include "TEST_ENABLED.h"
+#include "TEST_SEPARATOR.h"
+#include "TEST_VERSION.h"
+#include "TEST_BATCH_BYTES.h"
+#include "TEST_CRATE_NAME.h"
+#include "TEST_LARGE.h"
 #include <iostream>
 #include <cassert>
+#include <limits>
+#include <memory>
+#include <string_view>
+
+// Implementation of `TestStorage` trait, `multiplier` is shared to check Rust releases it
+class MapStorage : public TestStorage {
+public:
+    std::map<std::string, std::string> entries;
+    std::shared_ptr<i32> multiplier = std::make_shared<i32>(1);
+
+    std::optional<std::string> get(std::string key) const override {
+        auto entry = entries.find(key);
+        if (entry == entries.end())
+            return std::nullopt;
+        return entry->second;
+    }
+
+    void set(std::string key, std::string value) override {
+        entries[key] = value;
+    }
+
+    std::vector<std::string> keys() const override {
+        std::vector<std::string> keys;
+        for (const auto& [key, value] : entries)
+            keys.push_back(key);
+        return keys;
+    }
+
+    TestStruct2 scaled(TestStruct2 value, i32 factor) const override {
+        value.set_i32_field(value.get_i32_field() * factor * *multiplier);
+        return value;
+    }
+};
+
+class BracketFormatter : public TestFormatter {
+public:
+    std::string format(i32 value) const override {
+        return "[" + std::to_string(value) + "]";
+    }
+};
 
 int main()
 {
@@ -30,6 +158,9 @@ int main()
     test_struct.set_string_field("Hello, Rust!");
     std::cout << test_struct.get_string_field() << std::endl;
     assert(test_struct.get_string_field() == "Hello, Rust!");
+    assert(test_struct.string_field_ref() == "Hello, Rust!");
+    assert(test_struct.string_field_starts_with("Hello"));
+    assert(!test_struct.string_field_starts_with(std::string("Rust")));
     std::string str = "Hello, C++!";
     test_struct.set_string_field(str);
     std::cout << test_struct.get_string_field() << std::endl;
@@ -46,6 +177,132 @@ int main()
     std::cout << "struct setter test: " << test_struct.get_struct_field().get_i32_field() << std::endl;
     assert(test_struct.get_struct_field().get_i32_field() == 44);
 
+    // Methods
+    auto counter = TestStruct2();
+    counter.set_i32_field(44);
+    assert(counter.doubled() == 88);
+    assert(function_struct_value(counter) == 44);
+    counter.add(6);
+    assert(counter.get_i32_field() == 50);
+    assert(counter.describe("value: ") == "value: 50");
+    auto sum = counter.sum_with(test_struct2);
+    assert(sum.get_i32_field() == 93);
+    assert(counter.get_i32_field() == 50);
+    counter.apply([](i32 value) { return value * 3; });
+    assert(counter.get_i32_field() == 150);
+    counter.checked_set(20);
+    assert(counter.get_i32_field() == 20);
+
+    // Constructors
+    assert(TestStruct2(30).get_i32_field() == 30);
+    try {
+        TestStruct2(-5);
+        assert(false);
+    } catch (const hi_ffi::RustError& error) {
+        assert(error.message() == "value is negative");
+    }
+    auto default_point = TestPoint();
+    assert(default_point.get_x() == 1.0 && default_point.get_y() == 1.0);
+    auto point = TestPoint(3.0, 4.0);
+    assert(point.length() == 5.0);
+    assert(TestPoint::on_x_axis(2.0).get_y() == 0.0);
+    assert(TestCounter().get_count() == 0);
+    assert(TestCounter::create().get_count() == 1);
+    auto limited_counter = TestCounter::with_count(99);
+    assert(limited_counter.has_value() && limited_counter->try_increment().has_value() && limited_counter->get_count() == 100);
+    assert(limited_counter->try_increment().error().message() == "value is too large");
+    assert(TestCounter::with_count(101).error().message() == "value is too large");
+    auto parsed_u8 = function_parse_u8("7");
+    assert(parsed_u8.has_value() && *parsed_u8 == 7);
+    assert(function_parse_u8("300").error().message() == "number too large to fit in target type");
+    auto [x, y] = point.coords();
+    assert(x == 3.0 && y == 4.0);
+    assert(TestPoint::parse("6, 8").length() == 10.0);
+    try {
+        TestPoint::parse("6, eight");
+        assert(false);
+    } catch (const hi_ffi::RustError& error) {
+        assert(error.message() == "invalid value: eight");
+    }
+    try {
+        counter.checked_set(-1);
+        assert(false);
+    } catch (const hi_ffi::RustError& error) {
+        assert(error.message() == "value is negative");
+    }
+    assert(counter.get_i32_field() == 20);
+
+    assert(test_struct.get_enum_field() == TestEnum::First);
+    test_struct.set_enum_field(TestEnum::Third);
+    assert(test_struct.get_enum_field() == TestEnum::Third);
+    assert(static_cast<i32>(TestEnum::Third) == 6);
+
+    assert(test_struct.get_f64_vec_field().empty());
+    test_struct.set_f64_vec_field({1.5, 2.5, 3.5});
+    assert((test_struct.get_f64_vec_field() == std::vector<f64>{1.5, 2.5, 3.5}));
+    test_struct.set_bool_vec_field({true, false, true});
+    assert((test_struct.get_bool_vec_field() == std::vector<bool>{true, false, true}));
+    test_struct.set_string_vec_field({"first", "", "third"});
+    assert((test_struct.get_string_vec_field() == std::vector<std::string>{"first", "", "third"}));
+    test_struct.set_struct_vec_field({test_struct2, test_struct2_other});
+    auto struct_vec = test_struct.get_struct_vec_field();
+    assert(struct_vec.size() == 2);
+    assert(struct_vec[0].get_i32_field() == 43);
+    assert(struct_vec[1].get_i32_field() == 44);
+    struct_vec[0].set_i32_field(0); // elements own cloned values
+    assert(test_struct.get_struct_vec_field()[0].get_i32_field() == 43);
+
+    assert(!test_struct.get_optional_i32_field().has_value());
+    test_struct.set_optional_i32_field(7);
+    assert(test_struct.get_optional_i32_field() == 7);
+    test_struct.set_optional_i32_field(std::nullopt);
+    assert(!test_struct.get_optional_i32_field().has_value());
+    test_struct.set_optional_string_field("optional");
+    assert(test_struct.get_optional_string_field() == "optional");
+    assert(!test_struct.get_optional_struct_field().has_value());
+    test_struct.set_optional_struct_field(test_struct2_other);
+    assert(test_struct.get_optional_struct_field()->get_i32_field() == 44);
+
+    assert(test_struct.get_bytes_field().empty());
+    test_struct.set_bytes_field({0, 1, 255});
+    assert((test_struct.get_bytes_field() == std::vector<u8>{0, 1, 255}));
+
+    assert(test_struct.get_map_field().empty());
+    test_struct.set_map_field({{"key", "value"}, {"empty", ""}});
+    auto map_field = test_struct.get_map_field();
+    assert(map_field.size() == 2);
+    assert(map_field["key"] == "value" && map_field["empty"] == "");
+    assert(test_struct.get_char_field() == U'\0');
+    test_struct.set_char_field(U'\U0001F980');
+    assert(test_struct.get_char_field() == U'\U0001F980');
+    assert(test_struct.get_u128_field() == 0);
+    test_struct.set_u128_field(static_cast<u128>(1) << 100);
+    assert(test_struct.get_u128_field() == static_cast<u128>(1) << 100);
+
+    // Data enums
+    assert(test_struct.get_shape_field().tag() == TestShape::Tag::Empty);
+    auto circle = TestShape(TestShape::CircleVariant{2.5});
+    test_struct.set_shape_field(circle);
+    auto shape = test_struct.get_shape_field().variant();
+    assert(std::get<TestShape::CircleVariant>(shape).radius == 2.5);
+
+    auto rectangle = TestShape(TestShape::RectangleVariant{3.0, 4.0});
+    assert(rectangle.tag() == TestShape::Tag::Rectangle);
+    assert(std::get<TestShape::RectangleVariant>(rectangle.variant())._1 == 4.0);
+
+    auto named = TestShape(TestShape::NamedVariant{"Named shape"});
+    std::cout << std::get<TestShape::NamedVariant>(named.variant())._0 << std::endl;
+    assert(std::get<TestShape::NamedVariant>(named.variant())._0 == "Named shape");
+
+    auto wrapped = TestShape(TestShape::WrappedVariant{test_struct2_other});
+    assert(std::get<TestShape::WrappedVariant>(wrapped.variant())._0.get_i32_field() == 44);
+
+    auto with_enum = TestShape(TestShape::WithEnumVariant{TestEnum::Second});
+    assert(std::get<TestShape::WithEnumVariant>(with_enum.variant())._0 == TestEnum::Second);
+
+    auto polygon = TestShape(TestShape::PolygonVariant{{1.0, 2.0, 3.0}});
+    assert(std::get<TestShape::PolygonVariant>(polygon.variant())._0.size() == 3);
+
     // Functions
     simple_function();
     function_with_primitive_args(3, true);
@@ -57,4 +314,220 @@ int main()
     assert(function_return_string() == "String returned from Rust");
     std::cout << combo_function("Combo function!", "Don't print me", true) << std::endl;
     assert(combo_function("str1", "str2", true) == "str1");
+    assert(function_with_enum_arg(TestEnum::Third));
+    assert(!function_with_enum_arg(TestEnum::Second));
+    assert(function_return_enum(true) == TestReprEnum::High);
+    assert(static_cast<u8>(function_return_enum(false)) == 1);
+    assert(function_size_enum_value(TestSizeEnum::Large) == 4000000000);
+    try {
+        function_with_enum_arg(static_cast<TestEnum>(42));
+        assert(false);
+    } catch (const std::invalid_argument& error) {
+        assert(std::string(error.what()) == "Invalid TestEnum value");
+    }
+    assert(function_sum_vec({1, 2, 3, 4}) == 10);
+    assert(function_sum_vec({}) == 0);
+    assert((function_return_vec(3) == std::vector<f64>{0.0, 0.5, 1.0}));
+    assert(function_return_vec(0).empty());
+    assert((function_negate_vec({true, false}) == std::vector<bool>{false, true}));
+    assert(function_join_strings({"a", "b", "c"}, ", ") == "a, b, c");
+    assert((function_split_string("x,y,z") == std::vector<std::string>{"x", "y", "z"}));
+    auto structs = function_return_structs(4);
+    assert(structs.size() == 4);
+    assert(structs[3].get_i32_field() == 3);
+    assert(function_sum_structs(structs) == 6);
+    assert(function_optional_len("four") == 4u);
+    assert(!function_optional_len(std::nullopt).has_value());
+    assert(function_find_struct(structs, 2)->get_i32_field() == 2);
+    assert(!function_find_struct(structs, 5).has_value());
+    assert(function_parse_i32("42") == 42);
+    try {
+        function_parse_i32("not a number");
+        assert(false);
+    } catch (const hi_ffi::RustError& error) {
+        std::cout << "Rust error: " << error.what() << std::endl;
+        assert(error.message() == "invalid digit found in string");
+    }
+    function_check_value(50);
+    try {
+        function_check_value(500);
+        assert(false);
+    } catch (const hi_ffi::RustError& error) {
+        assert(std::string_view(error.what()) == "value is too large");
+    }
+    assert(function_parse_u32("7") == 7);
+    try {
+        function_parse_u32("-7");
+        assert(false);
+    } catch (const hi_ffi::RustError& error) {
+        assert(error.message() == "invalid value: -7");
+    }
+    auto scaled = function_scale_point(TestPoint(1.5, 2.0), 2.0);
+    assert(scaled.get_x() == 3.0 && scaled.get_y() == 4.0);
+    assert(function_make_struct(12).get_i32_field() == 12);
+    assert(function_shape_area(TestShape(TestShape::RectangleVariant{2.0, 3.0})) == 6.0);
+    assert(function_shape_area(TestShape(TestShape::EmptyVariant{})) == 0.0);
+    assert(function_item_value(TestItem(TestItem::TestStruct2Variant{test_struct2_other})) == 44);
+    auto item = TestItem(TestItem::TestEnumVariant{TestEnum::Third});
+    assert(std::get<TestItem::TestEnumVariant>(item.variant())._0 == TestEnum::Third);
+    assert(function_item_value(item) == 6);
+    assert(function_str_len("zażółć") == 6);
+    assert(function_str_len(std::string_view("abcdef", 3)) == 3);
+    assert(function_str_len("") == 0);
+    std::string_view level = function_level_name(true);
+    assert(level == "high");
+    assert(function_level_name(false) == "low");
+    assert((function_reverse_bytes({1, 2, 3}) == std::vector<u8>{3, 2, 1}));
+    assert(function_reverse_bytes({}).empty());
+    std::vector<u8> bytes = {10, 20, 30};
+    assert(function_checksum(bytes) == 60);
+    assert(function_checksum(hi_ffi::ByteSpan(bytes.data() + 1, 2)) == 50);
+    assert(function_checksum({}) == 0);
+    assert((function_repeat_byte(7, 3) == std::vector<u8>{7, 7, 7}));
+    assert(function_repeat_byte(7, 0).empty());
+    assert((function_parse_bytes("1 2 250") == std::vector<u8>{1, 2, 250}));
+    try {
+        function_parse_bytes("1 256");
+        assert(false);
+    } catch (const hi_ffi::RustError& error) {
+        assert(error.message() == "invalid value: 256");
+    }
+    assert((function_count_words("a b a c a") == std::map<std::string, u32>{{"a", 3}, {"b", 1}, {"c", 1}}));
+    assert(function_count_words("").empty());
+    assert(function_sum_scores({{"first", 40}, {"second", 2}}) == 42);
+    assert(function_sum_scores({}) == 0);
+    auto indexed = function_index_structs(structs);
+    assert(indexed.size() == 4);
+    assert(indexed.at(3).get_i32_field() == 3);
+    assert(function_sum_struct_map({{1u, test_struct2}, {2u, test_struct2_other}}) == 87);
+    assert((function_bounds({2.0, -1.0, 5.0}) == std::pair<f64, f64>{-1.0, 5.0}));
+    auto [first, second] = function_split_once("key=value", "=");
+    assert(first == "key" && second == "value");
+    assert(function_split_once("key", "=").second.empty());
+    assert(function_repeat_pair({"ab", 3}) == "ababab");
+    auto [tagged, tag] = function_tag_struct({TestEnum::Second, test_struct2, true});
+    assert(tagged.get_i32_field() == -43);
+    assert(tag == TestEnum::Second);
+    std::vector<u32> progress;
+    function_count_progress(3, [&progress](u32 step) { progress.push_back(step); });
+    assert((progress == std::vector<u32>{1, 2, 3}));
+    auto long_words = function_filter_words("a bb ccc dd", [](const std::string& word) { return word.size() > 1; });
+    assert((long_words == std::vector<std::string>{"bb", "ccc", "dd"}));
+    i32 visited_sum = 0;
+    function_visit_structs(structs, [&visited_sum](TestStruct2 value, TestEnum tag) {
+        assert(tag == TestEnum::Second);
+        visited_sum += value.get_i32_field();
+    });
+    assert(visited_sum == 6);
+    auto listener_state = std::make_shared<i32>(10);
+    function_set_listener([listener_state](TestEnum value) { return *listener_state + static_cast<i32>(value); });
+    assert(listener_state.use_count() == 2); // the listener is kept alive by Rust
+    assert(function_notify_listener(TestEnum::Third) == 16);
+    function_clear_listener();
+    assert(listener_state.use_count() == 1);
+    assert(!function_notify_listener(TestEnum::Third).has_value());
+    assert(function_call_on_thread(20, [](u32 value) { return value + 1; }) == 21);
+    assert((function_fill_storage(std::make_unique<MapStorage>(), {{"b", "2"}, {"a", "1"}}) == std::vector<std::string>{"a", "b"}));
+    auto storage = std::make_unique<MapStorage>();
+    storage->entries = {{"a", "1"}, {"c", "3"}};
+    assert(function_storage_summary(std::move(storage), {"a", "b", "c"}) == "a=1,b=-,c=3");
+    auto scaling_storage = std::make_unique<MapStorage>();
+    auto multiplier = scaling_storage->multiplier;
+    *multiplier = 2;
+    function_set_storage(std::move(scaling_storage));
+    assert(multiplier.use_count() == 2); // the implementation is kept alive by Rust
+    assert(function_storage_scale(TestStruct2(3), 5)->get_i32_field() == 30);
+    function_clear_storage();
+    assert(multiplier.use_count() == 1);
+    assert(!function_storage_scale(TestStruct2(3), 5).has_value());
+    assert((function_format_values(std::make_unique<BracketFormatter>(), {1, 2}) == std::vector<std::string>{"[1]", "[2]"}));
+    auto numbers_page = function_page_numbers(3);
+    assert((numbers_page.get_items() == std::vector<i32>{1, 2, 3}));
+    assert(numbers_page.get_cursor() == "after-3");
+    assert(numbers_page.sum() == 6);
+    auto structs_page = TestPageOfStructs();
+    structs_page.set_items({TestStruct2(4), TestStruct2(5)});
+    assert(function_page_total(structs_page) == 9);
+    assert((function_reverse_color({1, 2, 3, 4}) == std::array<u8, 4>{4, 3, 2, 1}));
+    auto identity = function_identity_matrix();
+    assert(identity[0] == 1.0f && identity[1] == 0.0f && identity[15] == 1.0f);
+    auto swapped = function_swap_corners({TestStruct2(1), TestStruct2(2)});
+    assert(swapped[0].get_i32_field() == 2 && swapped[1].get_i32_field() == 1);
+    auto transform = TestTransform();
+    assert((transform.get_color() == std::array<u8, 4>{}));
+    transform.set_color({255, 128, 0, 255});
+    transform.set_matrix(identity);
+    transform.set_corners(swapped);
+    assert((transform.get_color() == std::array<u8, 4>{255, 128, 0, 255}));
+    assert(transform.get_matrix() == identity);
+    assert(transform.get_corners()[1].get_i32_field() == 1);
+    assert(function_next_char(U'a') == U'b');
+    assert(function_next_char(U'\uD7FF') == U'\uD7FF'); // U+D800 is a surrogate
+    try {
+        function_next_char(static_cast<char32_t>(0xD800));
+        assert(false);
+    } catch (const std::invalid_argument& error) {
+        assert(std::string(error.what()) == "Invalid char value");
+    }
+    try {
+        function_next_char(static_cast<char32_t>(0x110000));
+        assert(false);
+    } catch (const std::invalid_argument&) {
+    }
+    assert((function_chars("añ🦀") == std::vector<char32_t>{U'a', U'ñ', U'🦀'}));
+    try {
+        function_count_chars("a\xff");
+        assert(false);
+    } catch (const std::invalid_argument& error) {
+        assert(std::string(error.what()) == "Invalid UTF-8 string");
+    }
+    try {
+        function_with_string_arg("\xed\xa0\x80"); // surrogate U+D800
+        assert(false);
+    } catch (const std::invalid_argument&) {
+    }
+    assert((function_count_chars("abca") == std::map<char32_t, u32>{{U'a', 2}, {U'b', 1}, {U'c', 1}}));
+    assert(function_map_chars("abc", [](char32_t value) { return value == U'b' ? U'🦀' : value; }) == "a🦀c");
+    assert(function_add_i128(-1, 1) == 0);
+    assert(function_add_i128(static_cast<i128>(1) << 70, -(static_cast<i128>(1) << 72)) == -(static_cast<i128>(3) << 70));
+    assert((function_u128_words(static_cast<u128>(5) << 64 | 7) == std::pair<u64, u64>{5, 7}));
+    assert(function_sum_i128({-(static_cast<i128>(1) << 80), 1, static_cast<i128>(1) << 80}) == 1);
+    assert(function_offset_index(SIZE_MAX - 1, 1) == SIZE_MAX);
+    assert(!function_offset_index(1, -2).has_value());
+    assert((function_word_lengths("a bb ccc") == std::vector<usize>{1, 2, 3}));
+    auto address = TestAddress();
+    address.set_street("Main Street");
+    address.set_number(7);
+    assert(function_format_address(address) == "7 Main Street");
+    TestLocation location = address;
+    assert(function_location_street(location) == "Main Street");
+    auto addresses = function_street_addresses({{"High Street", 1}, {"Low Street", 2}});
+    assert(addresses.size() == 2 && addresses[1].get_street() == "Low Street");
+    assert(function_checked_enum({1}) == TestEnum::Second);
+    try {
+        function_checked_enum({3});
+        assert(false);
+    } catch (const hi_ffi::RustError& error) {
+        assert(error.message() == "value is too large");
+    }
+    assert(function_count_enums({TestEnum::First, TestEnum::Third, TestEnum::Third}, TestEnum::Third) == 2);
+    TestUserId user_id = function_next_user_id(41);
+    assert(user_id == 42);
+    assert(function_checked_user_id(user_id) == 42);
+    try {
+        function_checked_user_id(0);
+        assert(false);
+    } catch (const hi_ffi::RustError& error) {
+        assert(error.message() == "user id can't be zero");
+    }
+    TestAddresses numbered = function_street_addresses({{"High Street", 1}, {"Low Street", 2}});
+    assert((function_address_numbers(numbered) == std::vector<TestUserId>{1, 2}));
+    static_assert(TEST_MAX_BATCH == 512 && TEST_OFFSET == -40 && TEST_RATIO == 2.5 && TEST_ENABLED);
+    static_assert(TEST_SEPARATOR == U'→');
+    static_assert(TEST_MAX_ID == std::numeric_limits<u64>::max());
+    static_assert(TEST_MIN_DELTA == std::numeric_limits<i64>::min());
+    static_assert(TEST_VERSION == "1.2 \"beta\"\n");
+    assert(TEST_BATCH_BYTES == 2048);
+    assert(TEST_CRATE_NAME == "tests");
+    assert(TEST_LARGE == (static_cast<u128>(5) << 64 | 7));
 }
\ No newline at end of file
@@ -0,0 +1,34 @@
+// Built with `cpp-expected` feature and `-fno-exceptions`, fallible functions return
+// `hi_ffi::Expected`
+#include "function_parse_i32.h"
+#include "function_check_value.h"
+#include "function_checked_user_id.h"
+#include "TestStruct2.h"
+#include "TestPoint.h"
+#include <cassert>
+#include <iostream>
+
+int main()
+{
+    auto parsed = function_parse_i32("42");
+    assert(parsed.has_value() && *parsed == 42);
+    auto invalid = function_parse_i32("forty two");
+    assert(!invalid);
+    assert(invalid.error().message() == "invalid digit found in string");
+
+    assert(function_check_value(50).has_value());
+    assert(function_check_value(-1).error().message() == "value is negative");
+    assert(function_checked_user_id(0).error().message() == "user id can't be zero");
+
+    auto value = TestStruct2::try_new(10);
+    assert(value.has_value() && value->get_i32_field() == 10);
+    assert(TestStruct2::try_new(101).error().message() == "value is too large");
+    assert(!value->checked_set(-5));
+    assert(value->get_i32_field() == 10);
+
+    auto point = TestPoint::parse("6, 8");
+    assert(point.has_value() && point->length() == 10.0);
+    assert(TestPoint::parse("6, eight").error().message() == "invalid value: eight");
+
+    std::cout << "cpp-expected example passed" << std::endl;
+}
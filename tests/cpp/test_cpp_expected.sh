@@ -0,0 +1,12 @@
+# Builds the tests crate with `cpp-expected` feature, runs the example without exceptions and
+# restores the default build used by `test_cpp.sh`
+(cd .. && touch src/lib.rs && cargo build --features cpp-expected) && \
+g++ -fno-exceptions ../generated_code/cpp/* main_expected.cpp \
+    -I ../generated_code/cpp/ \
+    -L ../target/debug/ \
+    -l tests \
+    -o test_expected && \
+./test_expected
+status=$?
+(cd .. && touch src/lib.rs && cargo build)
+exit $status
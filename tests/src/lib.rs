@@ -1,6 +1,96 @@
 use hi_ffi::ffi;
 
 use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::sync::Mutex;
+
+// items have to be declared before they are used by other items
+#[ffi]
+#[derive(Default, Clone, Copy, PartialEq, Debug, Serialize)]
+enum TestEnum {
+    #[default]
+    First,
+    Second = 5,
+    Third,
+}
+
+#[ffi]
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum TestReprEnum {
+    Low = 1,
+    High = 200,
+}
+
+#[ffi]
+#[repr(usize)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum TestSizeEnum {
+    Small = 1,
+    Large = 4000000000,
+}
+
+#[ffi]
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum TestError {
+    Negative,
+    TooLarge,
+}
+
+impl Display for TestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TestError::Negative => write!(f, "value is negative"),
+            TestError::TooLarge => write!(f, "value is too large"),
+        }
+    }
+}
+
+#[ffi]
+#[derive(Clone, Debug)]
+enum TestParseError {
+    Empty,
+    Invalid { value: String },
+}
+
+impl Display for TestParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TestParseError::Empty => write!(f, "empty value"),
+            TestParseError::Invalid { value } => write!(f, "invalid value: {value}"),
+        }
+    }
+}
+
+#[ffi]
+#[derive(Default, Clone, Serialize)]
+struct TestStruct2 {
+    pub i32_field: i32,
+}
+
+#[ffi]
+#[derive(Default, Clone, Serialize)]
+enum TestShape {
+    #[default]
+    Empty,
+    Circle {
+        radius: f64,
+    },
+    Rectangle(f64, f64),
+    Named(String),
+    Wrapped(TestStruct2),
+    WithEnum(TestEnum),
+    Polygon(Vec<f64>),
+}
+
+// variants named after their payload types
+#[ffi]
+#[derive(Clone)]
+enum TestItem {
+    TestStruct2(TestStruct2),
+    TestEnum(TestEnum),
+}
 
 #[ffi]
 #[derive(Default, Clone, Serialize)]
@@ -23,12 +113,209 @@ struct TestStruct {
 
     #[ffi(getter, setter)]
     struct_field: TestStruct2,
+
+    pub enum_field: TestEnum,
+
+    pub shape_field: TestShape,
+
+    pub f64_vec_field: Vec<f64>,
+
+    pub bool_vec_field: Vec<bool>,
+
+    pub string_vec_field: Vec<String>,
+
+    pub struct_vec_field: Vec<TestStruct2>,
+
+    pub optional_i32_field: Option<i32>,
+
+    pub optional_string_field: Option<String>,
+
+    pub optional_struct_field: Option<TestStruct2>,
+
+    pub bytes_field: Vec<u8>,
+
+    pub map_field: HashMap<String, String>,
+
+    pub char_field: char,
+
+    pub u128_field: u128,
 }
 
 #[ffi]
-#[derive(Default, Clone, Serialize)]
-struct TestStruct2 {
-    pub i32_field: i32,
+#[derive(Clone)]
+struct TestPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[ffi]
+impl Default for TestPoint {
+    fn default() -> Self {
+        TestPoint { x: 1.0, y: 1.0 }
+    }
+}
+
+#[ffi]
+impl TestPoint {
+    pub fn new(x: f64, y: f64) -> Self {
+        TestPoint { x, y }
+    }
+
+    #[ffi(constructor)]
+    fn on_x_axis(x: f64) -> Self {
+        TestPoint { x, y: 0.0 }
+    }
+
+    pub fn parse(value: String) -> Result<Self, TestParseError> {
+        let (x, y) = value.split_once(',').ok_or(TestParseError::Empty)?;
+        let parse = |value: &str| {
+            let value = value.trim();
+            value.parse().map_err(|_| TestParseError::Invalid {
+                value: value.to_string(),
+            })
+        };
+        Ok(TestPoint {
+            x: parse(x)?,
+            y: parse(y)?,
+        })
+    }
+
+    pub fn length(&self) -> f64 {
+        (self.x * self.x + self.y * self.y).sqrt()
+    }
+
+    pub fn coords(&self) -> (f64, f64) {
+        (self.x, self.y)
+    }
+}
+
+// `new` without arguments next to derived `default`
+#[ffi]
+#[derive(Default, Clone)]
+struct TestCounter {
+    pub count: u32,
+}
+
+#[ffi]
+impl TestCounter {
+    pub fn new() -> Self {
+        TestCounter { count: 1 }
+    }
+}
+
+// errors of `cpp_expected` items are returned in `hi_ffi::Expected` in C++ without the feature
+#[ffi(cpp_expected)]
+impl TestCounter {
+    pub fn with_count(count: u32) -> Result<Self, TestError> {
+        match count {
+            0..=100 => Ok(TestCounter { count }),
+            _ => Err(TestError::TooLarge),
+        }
+    }
+
+    pub fn try_increment(&mut self) -> Result<(), TestError> {
+        if self.count >= 100 {
+            return Err(TestError::TooLarge);
+        }
+        self.count += 1;
+        Ok(())
+    }
+}
+
+#[ffi]
+impl TestStruct2 {
+    pub fn new(i32_field: i32) -> Result<Self, TestError> {
+        function_check_value(i32_field)?;
+        Ok(TestStruct2 { i32_field })
+    }
+
+    pub fn doubled(&self) -> i32 {
+        self.i32_field * 2
+    }
+
+    pub fn add(&mut self, value: i32) {
+        self.i32_field += value;
+    }
+
+    pub fn describe(&self, prefix: String) -> String {
+        format!("{prefix}{}", self.i32_field)
+    }
+
+    pub fn sum_with(&self, other: TestStruct2) -> Self {
+        TestStruct2 {
+            i32_field: self.i32_field + other.i32_field,
+        }
+    }
+
+    pub fn apply(&mut self, f: impl Fn(i32) -> i32) {
+        self.i32_field = f(self.i32_field);
+    }
+
+    pub fn checked_set(&mut self, value: i32) -> Result<(), TestError> {
+        function_check_value(value)?;
+        self.i32_field = value;
+        Ok(())
+    }
+
+    // left for Rust only, so unsupported types are allowed
+    fn _not_exported(&self, out: &mut Vec<i32>) {
+        out.push(self.i32_field);
+    }
+
+    pub fn into_value(self) -> i32 {
+        let mut values = Vec::new();
+        self._not_exported(&mut values);
+        values[0]
+    }
+}
+
+#[ffi]
+impl TestStruct {
+    pub fn string_field_ref(&self) -> &str {
+        &self.string_field
+    }
+
+    pub fn string_field_starts_with(&self, prefix: &str) -> bool {
+        self.string_field.starts_with(prefix)
+    }
+}
+
+#[ffi]
+#[derive(Default, Clone)]
+struct TestTransform {
+    pub color: [u8; 4],
+    pub matrix: [f32; 16],
+    pub corners: [TestStruct2; 2],
+}
+
+// items of other modules are referred to with qualified paths
+mod model {
+    use super::*;
+
+    #[ffi]
+    #[derive(Default, Clone)]
+    pub struct TestAddress {
+        pub street: std::string::String,
+        pub number: core::primitive::u32,
+    }
+
+    // the aliased type is resolved in this module, while the alias is used in others
+    #[ffi]
+    pub type TestLocation = TestAddress;
+}
+
+#[ffi(instantiate(TestPageOfStructs = TestPage<TestStruct2>, TestPageOfInts = TestPage<i32>))]
+#[derive(Default, Clone)]
+struct TestPage<T> {
+    pub items: Vec<T>,
+    pub cursor: String,
+}
+
+#[ffi]
+impl TestPageOfInts {
+    pub fn sum(&self) -> i32 {
+        self.items.iter().sum()
+    }
 }
 
 #[ffi]
@@ -71,6 +358,529 @@ fn combo_function(str1: String, str2: String, b: bool) -> String {
     }
 }
 
+#[ffi]
+fn function_with_enum_arg(e: TestEnum) -> bool {
+    e == TestEnum::Third
+}
+
+#[ffi]
+fn function_return_enum(high: bool) -> TestReprEnum {
+    if high {
+        TestReprEnum::High
+    } else {
+        TestReprEnum::Low
+    }
+}
+
+#[ffi]
+fn function_size_enum_value(value: TestSizeEnum) -> usize {
+    value as usize
+}
+
+#[ffi]
+fn function_sum_vec(values: Vec<i32>) -> i32 {
+    values.iter().sum()
+}
+
+#[ffi]
+fn function_return_vec(len: u32) -> Vec<f64> {
+    (0..len).map(|i| i as f64 * 0.5).collect()
+}
+
+#[ffi]
+fn function_negate_vec(values: Vec<bool>) -> Vec<bool> {
+    values.into_iter().map(|value| !value).collect()
+}
+
+#[ffi]
+fn function_join_strings(values: Vec<String>, separator: String) -> String {
+    values.join(&separator)
+}
+
+#[ffi]
+fn function_split_string(value: String) -> Vec<String> {
+    value.split(',').map(str::to_owned).collect()
+}
+
+#[ffi]
+fn function_sum_structs(values: Vec<TestStruct2>) -> i32 {
+    values.iter().map(|value| value.i32_field).sum()
+}
+
+#[ffi]
+fn function_return_structs(len: i32) -> Vec<TestStruct2> {
+    (0..len)
+        .map(|i32_field| TestStruct2 { i32_field })
+        .collect()
+}
+
+#[ffi]
+fn function_count_enums(values: Vec<TestEnum>, value: TestEnum) -> u32 {
+    values.iter().filter(|item| **item == value).count() as u32
+}
+
+#[ffi]
+fn function_struct_value(value: TestStruct2) -> i32 {
+    value.into_value()
+}
+
+#[ffi]
+fn function_optional_len(value: Option<String>) -> Option<u32> {
+    value.map(|value| value.len() as u32)
+}
+
+#[ffi]
+fn function_find_struct(values: Vec<TestStruct2>, i32_field: i32) -> Option<TestStruct2> {
+    values
+        .into_iter()
+        .find(|value| value.i32_field == i32_field)
+}
+
+#[ffi]
+fn function_parse_i32(value: String) -> Result<i32, std::num::ParseIntError> {
+    value.parse()
+}
+
+#[ffi]
+fn function_check_value(value: i32) -> Result<(), TestError> {
+    if value < 0 {
+        Err(TestError::Negative)
+    } else if value > 100 {
+        Err(TestError::TooLarge)
+    } else {
+        Ok(())
+    }
+}
+
+#[ffi]
+fn function_parse_u32(value: String) -> Result<u32, TestParseError> {
+    if value.is_empty() {
+        return Err(TestParseError::Empty);
+    }
+    value.parse().map_err(|_| TestParseError::Invalid { value })
+}
+
+#[ffi]
+fn function_scale_point(point: TestPoint, factor: f64) -> TestPoint {
+    TestPoint::new(point.x * factor, point.y * factor)
+}
+
+#[ffi]
+fn function_make_struct(i32_field: i32) -> TestStruct2 {
+    TestStruct2 { i32_field }
+}
+
+#[ffi]
+fn function_shape_area(shape: TestShape) -> f64 {
+    match shape {
+        TestShape::Circle { radius } => std::f64::consts::PI * radius * radius,
+        TestShape::Rectangle(width, height) => width * height,
+        _ => 0.0,
+    }
+}
+
+#[ffi]
+fn function_item_value(item: TestItem) -> i32 {
+    match item {
+        TestItem::TestStruct2(value) => value.i32_field,
+        TestItem::TestEnum(value) => value as i32,
+    }
+}
+
+#[ffi]
+fn function_str_len(value: &str) -> u32 {
+    value.chars().count() as u32
+}
+
+#[ffi]
+fn function_level_name(high: bool) -> &'static str {
+    if high {
+        "high"
+    } else {
+        "low"
+    }
+}
+
+#[ffi]
+fn function_reverse_bytes(bytes: Vec<u8>) -> Vec<u8> {
+    bytes.into_iter().rev().collect()
+}
+
+#[ffi]
+fn function_checksum(bytes: &[u8]) -> u32 {
+    bytes.iter().map(|byte| *byte as u32).sum()
+}
+
+#[ffi]
+fn function_repeat_byte(byte: u8, len: u32) -> Box<[u8]> {
+    vec![byte; len as usize].into_boxed_slice()
+}
+
+#[ffi]
+fn function_parse_bytes(value: &str) -> Result<Vec<u8>, TestParseError> {
+    value
+        .split_whitespace()
+        .map(|byte| {
+            byte.parse().map_err(|_| TestParseError::Invalid {
+                value: byte.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[ffi]
+fn function_count_words(text: &str) -> BTreeMap<String, u32> {
+    let mut counts = BTreeMap::new();
+    for word in text.split_whitespace() {
+        *counts.entry(word.to_string()).or_insert(0) += 1;
+    }
+    counts
+}
+
+#[ffi]
+fn function_sum_scores(scores: HashMap<String, i64>) -> i64 {
+    scores.values().sum()
+}
+
+#[ffi]
+fn function_index_structs(values: Vec<TestStruct2>) -> BTreeMap<i32, TestStruct2> {
+    values
+        .into_iter()
+        .map(|value| (value.i32_field, value))
+        .collect()
+}
+
+#[ffi]
+fn function_sum_struct_map(values: HashMap<u32, TestStruct2>) -> i32 {
+    values.values().map(|value| value.i32_field).sum()
+}
+
+#[ffi]
+fn function_bounds(values: Vec<f64>) -> (f64, f64) {
+    values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(*value), max.max(*value))
+        })
+}
+
+#[ffi]
+fn function_split_once(value: String, separator: String) -> (String, String) {
+    match value.split_once(&separator) {
+        Some((first, second)) => (first.to_string(), second.to_string()),
+        None => (value, String::new()),
+    }
+}
+
+#[ffi]
+fn function_repeat_pair(pair: (String, u32)) -> String {
+    pair.0.repeat(pair.1 as usize)
+}
+
+#[ffi]
+fn function_tag_struct(value: (TestEnum, TestStruct2, bool)) -> (TestStruct2, TestEnum) {
+    let (tag, mut value, negate) = value;
+    if negate {
+        value.i32_field = -value.i32_field;
+    }
+    (value, tag)
+}
+
+#[ffi]
+fn function_count_progress(steps: u32, on_progress: impl Fn(u32)) {
+    (1..=steps).for_each(on_progress);
+}
+
+#[ffi]
+fn function_filter_words(
+    text: String,
+    mut keep: Box<dyn FnMut(String) -> bool + Send>,
+) -> Vec<String> {
+    text.split_whitespace()
+        .map(str::to_string)
+        .filter(|word| keep(word.clone()))
+        .collect()
+}
+
+#[ffi]
+fn function_visit_structs(values: Vec<TestStruct2>, visit: impl Fn(TestStruct2, TestEnum)) {
+    values
+        .into_iter()
+        .for_each(|value| visit(value, TestEnum::Second));
+}
+
+type TestListener = Box<dyn Fn(TestEnum) -> i32 + Send>;
+
+static TEST_LISTENER: Mutex<Option<TestListener>> = Mutex::new(None);
+
+#[ffi]
+fn function_set_listener(listener: Box<dyn Fn(TestEnum) -> i32 + Send>) {
+    *TEST_LISTENER.lock().unwrap() = Some(listener);
+}
+
+#[ffi]
+fn function_call_on_thread(value: u32, callback: Box<dyn Fn(u32) -> u32 + Send>) -> u32 {
+    std::thread::spawn(move || callback(value)).join().unwrap()
+}
+
+#[ffi]
+fn function_notify_listener(value: TestEnum) -> Option<i32> {
+    TEST_LISTENER
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|listener| listener(value))
+}
+
+#[ffi]
+fn function_clear_listener() {
+    *TEST_LISTENER.lock().unwrap() = None;
+}
+
+/// Key-value storage implemented by the caller, it can be stored and used from any thread
+#[ffi]
+trait TestStorage: Send {
+    fn get(&self, key: String) -> Option<String>;
+    fn set(&mut self, key: String, value: String);
+    fn keys(&self) -> Vec<String>;
+    fn scaled(&self, value: TestStruct2, factor: i32) -> TestStruct2;
+}
+
+#[ffi]
+fn function_fill_storage(
+    mut storage: impl TestStorage,
+    entries: BTreeMap<String, String>,
+) -> Vec<String> {
+    entries
+        .into_iter()
+        .for_each(|(key, value)| storage.set(key, value));
+    storage.keys()
+}
+
+#[ffi]
+fn function_storage_summary(storage: Box<dyn TestStorage>, keys: Vec<String>) -> String {
+    keys.into_iter()
+        .map(|key| {
+            let value = storage.get(key.clone()).unwrap_or_else(|| "-".to_string());
+            format!("{key}={value}")
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+static TEST_STORAGE: Mutex<Option<Box<dyn TestStorage + Send>>> = Mutex::new(None);
+
+#[ffi]
+fn function_set_storage(storage: Box<dyn TestStorage + Send>) {
+    *TEST_STORAGE.lock().unwrap() = Some(storage);
+}
+
+#[ffi]
+fn function_storage_scale(value: TestStruct2, factor: i32) -> Option<TestStruct2> {
+    TEST_STORAGE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|storage| storage.scaled(value, factor))
+}
+
+#[ffi]
+fn function_clear_storage() {
+    *TEST_STORAGE.lock().unwrap() = None;
+}
+
+/// Formatter implemented by the caller, used on the calling thread only
+#[ffi]
+trait TestFormatter {
+    fn format(&self, value: i32) -> String;
+}
+
+#[ffi]
+fn function_format_values(formatter: impl TestFormatter, values: Vec<i32>) -> Vec<String> {
+    values
+        .into_iter()
+        .map(|value| formatter.format(value))
+        .collect()
+}
+
+#[ffi]
+fn function_page_numbers(count: i32) -> TestPage<i32> {
+    TestPage {
+        items: (1..=count).collect(),
+        cursor: format!("after-{count}"),
+    }
+}
+
+#[ffi]
+fn function_page_total(page: TestPageOfStructs) -> i32 {
+    page.items.iter().map(|item| item.i32_field).sum()
+}
+
+#[ffi]
+fn function_reverse_color(color: [u8; 4]) -> [u8; 4] {
+    let [r, g, b, a] = color;
+    [a, b, g, r]
+}
+
+#[ffi]
+fn function_identity_matrix() -> [f32; 16] {
+    std::array::from_fn(|index| if index % 5 == 0 { 1.0 } else { 0.0 })
+}
+
+#[ffi]
+fn function_swap_corners(corners: [TestStruct2; 2]) -> [TestStruct2; 2] {
+    let [first, second] = corners;
+    [second, first]
+}
+
+#[ffi]
+fn function_next_char(value: char) -> char {
+    char::from_u32(u32::from(value) + 1).unwrap_or(value)
+}
+
+#[ffi]
+fn function_chars(value: &str) -> Vec<char> {
+    value.chars().collect()
+}
+
+#[ffi]
+fn function_count_chars(value: &str) -> BTreeMap<char, u32> {
+    value.chars().fold(BTreeMap::new(), |mut counts, c| {
+        *counts.entry(c).or_default() += 1;
+        counts
+    })
+}
+
+#[ffi]
+fn function_map_chars(value: &str, map: impl Fn(char) -> char) -> String {
+    value.chars().map(map).collect()
+}
+
+#[ffi]
+fn function_add_i128(a: i128, b: i128) -> i128 {
+    a.wrapping_add(b)
+}
+
+#[ffi]
+fn function_u128_words(value: u128) -> (u64, u64) {
+    ((value >> 64) as u64, value as u64)
+}
+
+#[ffi]
+fn function_sum_i128(values: Vec<i128>) -> i128 {
+    values.into_iter().sum()
+}
+
+#[ffi]
+fn function_offset_index(index: usize, delta: isize) -> Option<usize> {
+    index.checked_add_signed(delta)
+}
+
+#[ffi]
+fn function_word_lengths(value: &str) -> Vec<usize> {
+    value.split_whitespace().map(str::len).collect()
+}
+
+#[ffi]
+fn function_format_address(address: crate::model::TestAddress) -> std::string::String {
+    format!("{} {}", address.number, address.street)
+}
+
+#[ffi]
+fn function_street_addresses(
+    streets: std::collections::BTreeMap<std::string::String, u32>,
+) -> std::vec::Vec<model::TestAddress> {
+    streets
+        .into_iter()
+        .map(|(street, number)| model::TestAddress { street, number })
+        .collect()
+}
+
+#[ffi]
+fn function_checked_enum(
+    value: ::std::vec::Vec<std::primitive::u8>,
+) -> std::result::Result<self::TestEnum, crate::TestError> {
+    match value.as_slice() {
+        [] => Err(TestError::Negative),
+        [first, ..] if *first > 2 => Err(TestError::TooLarge),
+        [first, ..] => Ok([TestEnum::First, TestEnum::Second, TestEnum::Third][*first as usize]),
+    }
+}
+
+#[ffi]
+pub type TestUserId = u64;
+
+#[ffi]
+pub type TestAddresses = Vec<model::TestAddress>;
+
+// errors of types other than `#[ffi]` enums carry only the message
+#[ffi]
+pub type TestMessage = String;
+
+#[ffi(cpp_expected)]
+fn function_parse_u8(value: &str) -> Result<u8, std::num::ParseIntError> {
+    value.parse()
+}
+
+#[ffi]
+fn function_checked_user_id(id: TestUserId) -> Result<TestUserId, TestMessage> {
+    match id {
+        0 => Err("user id can't be zero".to_string()),
+        id => Ok(id),
+    }
+}
+
+#[ffi]
+fn function_next_user_id(id: TestUserId) -> TestUserId {
+    id + 1
+}
+
+#[ffi]
+fn function_location_street(location: model::TestLocation) -> String {
+    location.street
+}
+
+#[ffi]
+fn function_address_numbers(addresses: TestAddresses) -> Vec<TestUserId> {
+    addresses
+        .into_iter()
+        .map(|address| TestUserId::from(address.number))
+        .collect()
+}
+
+#[ffi]
+pub const TEST_MAX_BATCH: u32 = 512;
+
+#[ffi]
+pub const TEST_OFFSET: i16 = -40;
+
+#[ffi]
+pub const TEST_MAX_ID: u64 = 18446744073709551615;
+
+#[ffi]
+pub const TEST_MIN_DELTA: i64 = -9223372036854775808;
+
+#[ffi]
+pub const TEST_RATIO: f64 = 2.5;
+
+#[ffi]
+pub const TEST_ENABLED: bool = true;
+
+#[ffi]
+pub const TEST_SEPARATOR: char = '→';
+
+#[ffi]
+pub static TEST_VERSION: &str = "1.2 \"beta\"\n";
+
+#[ffi]
+pub const TEST_BATCH_BYTES: usize = TEST_MAX_BATCH as usize * 4;
+
+#[ffi]
+pub static TEST_CRATE_NAME: &str = env!("CARGO_PKG_NAME");
+
+#[ffi]
+pub const TEST_LARGE: u128 = 5 << 64 | 7;
+
 // Having Drop defined causes still reachable resources in valgrind report
 // impl Drop for TestStruct {
 //     fn drop(&mut self) {
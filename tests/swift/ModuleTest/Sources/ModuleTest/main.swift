@@ -5,6 +5,35 @@ import FfiModule
 
 // TODO assertions
 
+// Implementation of `TestStorage` trait
+struct DictionaryStorage: TestStorage {
+    var entries: [String: String] = [:]
+    var multiplier: i32 = 1
+
+    func get(_ key: String) -> String? {
+        return entries[key]
+    }
+
+    mutating func set(_ key: String, _ value: String) {
+        entries[key] = value
+    }
+
+    func keys() -> [String] {
+        return entries.keys.sorted()
+    }
+
+    func scaled(_ value: TestStruct2, _ factor: i32) -> TestStruct2 {
+        value.i32_field *= factor * multiplier
+        return value
+    }
+}
+
+struct BracketFormatter: TestFormatter {
+    func format(_ value: i32) -> String {
+        return "[\(value)]"
+    }
+}
+
 func run () {
     print("Swift FFI Test Suite")
 
@@ -30,6 +59,231 @@ func run () {
 
     print(combo_function("Combo!", "Don't print me", true))
 
+    print("Calling function_with_enum_arg")
+    print("function_with_enum_arg(.Third): \(function_with_enum_arg(.Third))")
+
+    print("Calling function_return_enum")
+    print("function_return_enum(true): \(function_return_enum(true))")
+
+    print("Calling function_size_enum_value")
+    print("function_size_enum_value(.Large): \(function_size_enum_value(.Large))")
+
+    print("Calling function_sum_vec")
+    print("function_sum_vec([1, 2, 3, 4]): \(function_sum_vec([1, 2, 3, 4]))")
+
+    print("Calling function_return_vec")
+    print("function_return_vec(3): \(function_return_vec(3))")
+
+    print("Calling function_negate_vec")
+    print("function_negate_vec([true, false]): \(function_negate_vec([true, false]))")
+
+    print("Calling function_join_strings")
+    print("function_join_strings([\"a\", \"b\", \"c\"], \", \"): \(function_join_strings(["a", "b", "c"], ", "))")
+
+    print("Calling function_split_string")
+    print("function_split_string(\"x,y,z\"): \(function_split_string("x,y,z"))")
+
+    print("Calling function_return_structs")
+    let structs = function_return_structs(4)
+    print("function_return_structs(4).count: \(structs.count)")
+
+    print("Calling function_sum_structs")
+    print("function_sum_structs(structs): \(function_sum_structs(structs))")
+
+    print("Calling function_optional_len")
+    print("function_optional_len(\"four\"): \(String(describing: function_optional_len("four")))")
+    print("function_optional_len(nil): \(String(describing: function_optional_len(nil)))")
+
+    print("Calling function_find_struct")
+    print("function_find_struct(structs, 2): \(String(describing: function_find_struct(structs, 2)?.i32_field))")
+
+    print("Calling function_parse_i32")
+    do {
+        print("function_parse_i32(\"42\"): \(try function_parse_i32("42"))")
+        _ = try function_parse_i32("not a number")
+    } catch let error as RustError {
+        print("function_parse_i32 error: \(error.message)")
+    } catch {
+        print("unexpected error: \(error)")
+    }
+
+    print("Calling function_check_value")
+    do {
+        try function_check_value(500)
+    } catch let error as RustEnumError<TestError> {
+        print("function_check_value error: \(error.message), variant: \(error.variant)")
+    } catch {
+        print("unexpected error: \(error)")
+    }
+
+    print("Calling function_parse_u32")
+    do {
+        _ = try function_parse_u32("-7")
+    } catch let error as RustEnumError<TestParseError> {
+        print("function_parse_u32 error: \(error.message), variant: \(error.variant)")
+    } catch {
+        print("unexpected error: \(error)")
+    }
+
+    print("Passing structs to functions")
+    let scaled = function_scale_point(TestPoint(1.5, 2.0), 2.0)
+    print("function_scale_point(TestPoint(1.5, 2), 2) (should be 3, 4): \(scaled.x), \(scaled.y)")
+    print("function_make_struct(12).i32_field: \(function_make_struct(12).i32_field)")
+    print("function_shape_area(.Rectangle(2, 3)): \(function_shape_area(.Rectangle(2.0, 3.0)))")
+    print("function_item_value(.TestEnum(.Third)) (should be 6): \(function_item_value(.TestEnum(.Third)))")
+
+    print("Passing string slices")
+    print("function_str_len(\"zażółć\") (should be 6): \(function_str_len("zażółć"))")
+    print("function_level_name(true): \(function_level_name(true))")
+
+    print("Passing byte buffers")
+    print("function_reverse_bytes(Data([1, 2, 3])): \([UInt8](function_reverse_bytes(Data([1, 2, 3]))))")
+    print("function_checksum(Data([10, 20, 30])) (should be 60): \(function_checksum(Data([10, 20, 30])))")
+    print("function_repeat_byte(7, 3): \([UInt8](function_repeat_byte(7, 3)))")
+    do {
+        print("function_parse_bytes(\"1 2 250\"): \([UInt8](try function_parse_bytes("1 2 250")))")
+        _ = try function_parse_bytes("1 256")
+    } catch let error as RustEnumError<TestParseError> {
+        print("function_parse_bytes error: \(error.message), variant: \(error.variant)")
+    } catch {
+        print("unexpected error: \(error)")
+    }
+
+    print("Passing maps")
+    print("function_count_words(\"a b a c a\"): \(function_count_words("a b a c a"))")
+    print("function_sum_scores([\"first\": 40, \"second\": 2]) (should be 42): \(function_sum_scores(["first": 40, "second": 2]))")
+    let indexed = function_index_structs(structs)
+    print("function_index_structs(structs)[3]: \(String(describing: indexed[3]?.i32_field))")
+    print("function_sum_struct_map([1: structs[1], 2: structs[2]]) (should be 3): \(function_sum_struct_map([1: structs[1], 2: structs[2]]))")
+
+    print("Passing tuples")
+    print("function_bounds([2, -1, 5]) (should be (-1.0, 5.0)): \(function_bounds([2.0, -1.0, 5.0]))")
+    print("function_split_once(\"key=value\", \"=\"): \(function_split_once("key=value", "="))")
+    print("function_repeat_pair((\"ab\", 3)) (should be ababab): \(function_repeat_pair(("ab", 3)))")
+    let (tagged, tag) = function_tag_struct((.Second, structs[2], true))
+    print("function_tag_struct((.Second, structs[2], true)) (should be -2, Second): \(tagged.i32_field), \(tag)")
+
+    print("Passing callbacks")
+    var progress: [u32] = []
+    function_count_progress(3) { step in progress.append(step) }
+    print("function_count_progress(3) (should be [1, 2, 3]): \(progress)")
+    print("function_filter_words(\"a bb ccc dd\"): \(function_filter_words("a bb ccc dd") { word in word.count > 1 })")
+    var visitedSum: i32 = 0
+    function_visit_structs(structs) { value, tag in visitedSum += value.i32_field }
+    print("function_visit_structs(structs) sum (should be 6): \(visitedSum)")
+    function_set_listener { value in 10 + value.rawValue }
+    print("function_notify_listener(.Third) (should be 16): \(String(describing: function_notify_listener(.Third)))")
+    function_clear_listener()
+    print("function_notify_listener(.Third) (should be nil): \(String(describing: function_notify_listener(.Third)))")
+    print("function_call_on_thread(20) (should be 21): \(function_call_on_thread(20) { value in value + 1 })")
+    print("function_fill_storage(DictionaryStorage(), [b: 2, a: 1]) (should be [a, b]): \(function_fill_storage(DictionaryStorage(), ["b": "2", "a": "1"]))")
+    let storage = DictionaryStorage(entries: ["a": "1", "c": "3"])
+    print("function_storage_summary(storage, [a, b, c]) (should be a=1,b=-,c=3): \(function_storage_summary(storage, ["a", "b", "c"]))")
+    function_set_storage(DictionaryStorage(multiplier: 2))
+    print("function_storage_scale(TestStruct2(3), 5) (should be 30): \(String(describing: function_storage_scale(try! TestStruct2(3), 5)?.i32_field))")
+    function_clear_storage()
+    print("function_storage_scale(TestStruct2(3), 5) (should be nil): \(String(describing: function_storage_scale(try! TestStruct2(3), 5)?.i32_field))")
+    print("function_format_values(BracketFormatter(), [1, 2]) (should be [[1], [2]]): \(function_format_values(BracketFormatter(), [1, 2]))")
+    let numbersPage = function_page_numbers(3)
+    print("function_page_numbers(3) (should be [1, 2, 3], after-3, 6): \(numbersPage.items), \(numbersPage.cursor), \(numbersPage.sum())")
+    let structsPage = TestPageOfStructs()
+    structsPage.items = [try! TestStruct2(4), try! TestStruct2(5)]
+    print("function_page_total(structsPage) (should be 9): \(function_page_total(structsPage))")
+    print("function_reverse_color([1, 2, 3, 4]) (should be [4, 3, 2, 1]): \(function_reverse_color([1, 2, 3, 4]))")
+    let identity = function_identity_matrix()
+    print("function_identity_matrix() (should be 1.0 on the diagonal): \(identity)")
+    print("function_swap_corners([1, 2]) (should be [2, 1]): \(function_swap_corners([try! TestStruct2(1), try! TestStruct2(2)]).map { $0.i32_field })")
+    let transform = TestTransform()
+    transform.color = [255, 128, 0, 255]
+    transform.matrix = identity
+    transform.corners = [try! TestStruct2(3), try! TestStruct2(4)]
+    print("transform (should be [255, 128, 0, 255], 16, [3, 4]): \(transform.color), \(transform.matrix.count), \(transform.corners.map { $0.i32_field })")
+    print("function_next_char(\"a\") (should be b): \(function_next_char("a"))")
+    // surrogates can't be passed to Rust, as they aren't `Unicode.Scalar`s
+    print("Unicode.Scalar(0xD800) (should be nil): \(String(describing: Unicode.Scalar(UInt32(0xD800))))")
+    print("function_chars(\"añ🦀\") (should be [a, ñ, 🦀]): \(function_chars("añ🦀"))")
+    print("function_count_chars(\"abca\") (should be [a: 2, b: 1, c: 1]): \(function_count_chars("abca"))")
+    print("function_map_chars(\"abc\") (should be a🦀c): \(function_map_chars("abc") { value in value == "b" ? "🦀" : value })")
+    print("function_add_i128(-1, 1) (should be 0): \(function_add_i128(i128(-1), i128(1)) == i128(0))")
+    print("function_u128_words(u128(lo: 7, hi: 5)) (should be (5, 7)): \(function_u128_words(u128(lo: 7, hi: 5)))")
+    print("function_sum_i128([-2, 3]) (should be 1): \(function_sum_i128([i128(-2), i128(3)]) == i128(1))")
+    print("function_offset_index(5, -2) (should be 3): \(String(describing: function_offset_index(5, -2)))")
+    print("function_offset_index(1, -2) (should be nil): \(String(describing: function_offset_index(1, -2)))")
+    print("function_word_lengths(\"a bb ccc\") (should be [1, 2, 3]): \(function_word_lengths("a bb ccc"))")
+    let address = TestAddress()
+    address.street = "Main Street"
+    address.number = 7
+    print("function_format_address(address) (should be 7 Main Street): \(function_format_address(address))")
+    let location: TestLocation = address
+    print("function_location_street(location) (should be Main Street): \(function_location_street(location))")
+    print("function_street_addresses([High Street: 1, Low Street: 2]) streets: \(function_street_addresses(["High Street": 1, "Low Street": 2]).map { $0.street })")
+    print("function_checked_enum([1]) (should be Second): \(try! function_checked_enum(Data([1])))")
+    let userId: TestUserId = function_next_user_id(41)
+    print("function_next_user_id(41) (should be 42): \(userId)")
+    do {
+        _ = try function_checked_user_id(0)
+    } catch let error as RustError {
+        print("function_checked_user_id(0) error: \(error.message)")
+    } catch {
+        print("Unexpected error: \(error)")
+    }
+    let addresses: TestAddresses = function_street_addresses(["High Street": 1, "Low Street": 2])
+    print("function_address_numbers(addresses) (should be [1, 2]): \(function_address_numbers(addresses))")
+
+    print("Reading constants")
+    print("TEST_MAX_BATCH (should be 512): \(TEST_MAX_BATCH), TEST_OFFSET (should be -40): \(TEST_OFFSET)")
+    print("TEST_MAX_ID (should be 18446744073709551615): \(TEST_MAX_ID), TEST_MIN_DELTA (should be -9223372036854775808): \(TEST_MIN_DELTA)")
+    print("TEST_RATIO (should be 2.5): \(TEST_RATIO), TEST_ENABLED (should be true): \(TEST_ENABLED)")
+    print("TEST_SEPARATOR (should be →): \(TEST_SEPARATOR), TEST_VERSION: \(TEST_VERSION)")
+    print("TEST_BATCH_BYTES (should be 2048): \(TEST_BATCH_BYTES), TEST_CRATE_NAME (should be tests): \(TEST_CRATE_NAME)")
+    print("TEST_LARGE (should be lo: 7, hi: 5): \(TEST_LARGE)")
+
+    print("Calling function_count_enums")
+    print("function_count_enums([.First, .Third, .Third], .Third): \(function_count_enums([.First, .Third, .Third], .Third))")
+
+    print("Calling methods")
+    let counter = TestStruct2()
+    counter.add(21)
+    print("counter.doubled() (should be 42): \(counter.doubled())")
+    print("function_struct_value(counter) (should be 21): \(function_struct_value(counter))")
+    counter.apply { value in value * 2 }
+    counter.add(-21)
+    print("counter.describe(\"value: \"): \(counter.describe("value: "))")
+    print("counter.sum_with(counter): \(counter.sum_with(counter).i32_field)")
+    do {
+        try counter.checked_set(500)
+    } catch let error as RustEnumError<TestError> {
+        print("checked_set error: \(error.message), variant: \(error.variant)")
+    } catch {
+        print("unexpected error: \(error)")
+    }
+
+    print("Calling constructors")
+    print("TestStruct2(30).i32_field: \(try! TestStruct2(30).i32_field)")
+    do {
+        _ = try TestStruct2(-5)
+    } catch let error as RustEnumError<TestError> {
+        print("TestStruct2(-5) error: \(error.message), variant: \(error.variant)")
+    } catch {
+        print("unexpected error: \(error)")
+    }
+    print("TestPoint() (should be 1, 1): \(TestPoint().x), \(TestPoint().y)")
+    print("TestPoint(3, 4).length() (should be 5): \(TestPoint(3.0, 4.0).length())")
+    print("TestPoint.on_x_axis(2).y (should be 0): \(TestPoint.on_x_axis(2.0).y)")
+    print("TestCounter().count (should be 0): \(TestCounter().count), TestCounter.new().count (should be 1): \(TestCounter.new().count)")
+    let counter = try! TestCounter.with_count(99)
+    try! counter.try_increment()
+    print("counter.count (should be 100): \(counter.count), function_parse_u8(\"300\") (should be nil): \(String(describing: try? function_parse_u8("300")))")
+    print("TestPoint(3, 4).coords() (should be (3.0, 4.0)): \(TestPoint(3.0, 4.0).coords())")
+    do {
+        print("TestPoint.parse(\"6, 8\").length(): \(try TestPoint.parse("6, 8").length())")
+        _ = try TestPoint.parse("6, eight")
+    } catch let error as RustEnumError<TestParseError> {
+        print("TestPoint.parse error: \(error.message), variant: \(error.variant)")
+    } catch {
+        print("unexpected error: \(error)")
+    }
+
     print("Creating a struct")
     let s = TestStruct()
 
@@ -48,6 +302,53 @@ func run () {
     print("Setting string field")
     s.string_field = "Hello, World!"
     print("updated string_field: \(s.string_field)")
+    print("string_field_ref(): \(s.string_field_ref())")
+    print("string_field_starts_with(\"Hello\"): \(s.string_field_starts_with("Hello"))")
+
+    print("Setting enum field")
+    s.enum_field = .Second
+    print("updated enum_field: \(s.enum_field)")
+
+    print("Setting vector fields")
+    s.f64_vec_field = [1.5, 2.5, 3.5]
+    print("updated f64_vec_field: \(s.f64_vec_field)")
+    s.bool_vec_field = [true, false, true]
+    print("updated bool_vec_field: \(s.bool_vec_field)")
+    s.string_vec_field = ["first", "", "third"]
+    print("updated string_vec_field: \(s.string_vec_field)")
+    s.struct_vec_field = structs
+    print("updated struct_vec_field: \(s.struct_vec_field.map { $0.i32_field })")
+
+    print("Setting optional fields")
+    print("optional_i32_field (should be nil): \(String(describing: s.optional_i32_field))")
+    s.optional_i32_field = 7
+    print("updated optional_i32_field: \(String(describing: s.optional_i32_field))")
+    s.optional_string_field = "optional"
+    print("updated optional_string_field: \(String(describing: s.optional_string_field))")
+    s.optional_struct_field = structs[1]
+    print("updated optional_struct_field: \(String(describing: s.optional_struct_field?.i32_field))")
+
+    print("Setting bytes field")
+    s.bytes_field = Data([0, 1, 255])
+    print("updated bytes_field: \([UInt8](s.bytes_field))")
+
+    print("Setting map field")
+    s.map_field = ["key": "value", "empty": ""]
+    print("updated map_field: \(s.map_field)")
+
+    print("Setting char field")
+    s.char_field = "🦀"
+    print("updated char_field: \(s.char_field)")
+
+    print("Setting u128 field")
+    s.u128_field = u128(lo: 7, hi: 5)
+    print("updated u128_field (should be lo: 7, hi: 5): \(s.u128_field)")
+
+    print("Passing data enum through Rust")
+    let shape = TestShape(TestShape.Circle(radius: 2.5).toRawPtr())
+    print("shape: \(shape)")
+    let named = TestShape(TestShape.Named("Named shape").toRawPtr())
+    print("named: \(named)")
 }
 
 run()
\ No newline at end of file